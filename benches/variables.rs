@@ -0,0 +1,46 @@
+//! Variable-heavy evaluation benchmark. Run with `cargo bench --bench variables`.
+
+use std::time::{Duration, Instant};
+
+use owl::{evaluator::Evaluator, reader::Reader, values::Env};
+
+const SETUP: &str = r#"
+(def alpha 1) (def beta 2) (def gamma 3) (def delta 4)
+(fun sum-to (n acc)
+  (if (= n 0)
+    acc
+    (sum-to (- n 1) (+ acc alpha beta gamma delta n))))
+(fun make-adder (x)
+  (fn (y) (fn (z) (+ x y z alpha beta))))
+"#;
+
+fn run(name: &str, code: &str, iterations: u32) -> Duration {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.eval(&env, SETUP).unwrap();
+    let form = Reader::new().read_script(code).unwrap();
+    evaluator.evaluate(&env, &form).unwrap();
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        evaluator.evaluate(&env, &form).unwrap();
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{:<16} {:>10.2?} per iteration ({} iterations)",
+        name,
+        elapsed / iterations,
+        iterations
+    );
+    elapsed
+}
+
+fn main() {
+    run("recursion", "(sum-to 300 0)", 200);
+    run("closures", "(((make-adder 1) 2) 3)", 20_000);
+    run(
+        "globals",
+        "(+ alpha beta gamma delta alpha beta gamma delta)",
+        20_000,
+    );
+}
@@ -0,0 +1,287 @@
+use std::fmt::{self, Write};
+
+use crate::values::Span;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A span of source with a short note about it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// A message about a script, pointing at the source it concerns.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// A stable code for the kind of problem, such as `E0002`.
+    pub code: Option<String>,
+    pub message: String,
+    /// Where the problem is.
+    pub primary: Option<Label>,
+    /// Other places that explain it, such as where a list was opened.
+    pub related: Vec<Label>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: None,
+            message: message.into(),
+            primary: None,
+            related: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn with_code<C: Into<String>>(mut self, code: C) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    pub fn with_primary<M: Into<String>>(mut self, span: Span, message: M) -> Self {
+        self.primary = Some(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_related<M: Into<String>>(mut self, span: Span, message: M) -> Self {
+        self.related.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_help<M: Into<String>>(mut self, help: M) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Renders the diagnostic like rustc does: a header, the `file:line:col`
+    /// of the problem and the source lines involved, with the primary span
+    /// underlined by `^` and related ones by `-`. ANSI colors are used if
+    /// `color` is set.
+    pub fn render(&self, color: bool) -> String {
+        let style = Style { color };
+        let mut out = String::new();
+        let mut severity = self.severity.to_string();
+        if let Some(code) = &self.code {
+            let _ = write!(severity, "[{}]", code);
+        }
+        let severity = match self.severity {
+            Severity::Error => style.paint(RED, &severity),
+            Severity::Warning => style.paint(YELLOW, &severity),
+        };
+        let _ = writeln!(
+            out,
+            "{}{}",
+            severity,
+            style.paint(BOLD, &format!(": {}", self.message))
+        );
+
+        let labels = self
+            .primary
+            .iter()
+            .map(|label| (label, true))
+            .chain(self.related.iter().map(|label| (label, false)))
+            .collect::<Vec<_>>();
+        let width = labels
+            .iter()
+            .map(|(label, _)| label.span.line_col().0.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let pad = " ".repeat(width);
+        let gutter = style.paint(BLUE, &format!("{} |", pad));
+
+        // Labels in the same source share a snippet, in line order.
+        let mut groups: Vec<Vec<(&Label, bool)>> = Vec::new();
+        for (label, primary) in labels {
+            match groups
+                .iter_mut()
+                .find(|group| group[0].0.span.source == label.span.source)
+            {
+                Some(group) => group.push((label, primary)),
+                None => groups.push(vec![(label, primary)]),
+            }
+        }
+        for (i, group) in groups.iter_mut().enumerate() {
+            let arrow = if i == 0 { "-->" } else { ":::" };
+            let _ = writeln!(
+                out,
+                "{}{} {}",
+                pad,
+                style.paint(BLUE, arrow),
+                group[0].0.span
+            );
+            let _ = writeln!(out, "{}", gutter);
+            group.sort_by_key(|(label, _)| label.span.start);
+            let mut shown = None;
+            for (label, primary) in group.iter() {
+                let (line, column) = label.span.line_col();
+                if shown != Some(line) {
+                    let text = label.span.source.text.lines().nth(line - 1).unwrap_or("");
+                    let number = style.paint(BLUE, &format!("{:>width$} |", line));
+                    let _ = writeln!(out, "{} {}", number, text);
+                    shown = Some(line);
+                }
+                let (mark, paint) = if *primary { ('^', RED) } else { ('-', BLUE) };
+                let marks = mark.to_string().repeat(underline_width(&label.span));
+                let note = if label.message.is_empty() {
+                    marks
+                } else {
+                    format!("{} {}", marks, label.message)
+                };
+                let _ = writeln!(
+                    out,
+                    "{} {}{}",
+                    gutter,
+                    " ".repeat(column - 1),
+                    style.paint(paint, &note)
+                );
+            }
+        }
+        if let Some(help) = &self.help {
+            if !groups.is_empty() {
+                let _ = writeln!(out, "{}", gutter);
+            }
+            let _ = writeln!(
+                out,
+                "{} {} {}",
+                pad,
+                style.paint(BLUE, "="),
+                style.paint(BOLD, &format!("help: {}", help))
+            );
+        }
+        out
+    }
+}
+
+impl Diagnostic {
+    /// Renders the diagnostic as a single-line JSON object for editors and
+    /// CI tools. Lines and columns are 1-based, and a range ends just past
+    /// its last character. `file` and `range` are null if the problem has
+    /// no known source.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{");
+        let _ = write!(
+            out,
+            "\"severity\":{}",
+            json_string(&self.severity.to_string())
+        );
+        let _ = write!(out, ",\"code\":{}", json_option(self.code.as_deref()));
+        let _ = write!(out, ",\"message\":{}", json_string(&self.message));
+        match &self.primary {
+            Some(label) => {
+                let _ = write!(
+                    out,
+                    ",\"file\":{},\"range\":{},\"label\":{}",
+                    json_string(&label.span.source.name),
+                    json_range(&label.span),
+                    json_string(&label.message)
+                );
+            }
+            None => out.push_str(",\"file\":null,\"range\":null,\"label\":null"),
+        }
+        out.push_str(",\"related\":[");
+        for (i, label) in self.related.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(
+                out,
+                "{{\"file\":{},\"range\":{},\"message\":{}}}",
+                json_string(&label.span.source.name),
+                json_range(&label.span),
+                json_string(&label.message)
+            );
+        }
+        let _ = write!(out, "],\"help\":{}}}", json_option(self.help.as_deref()));
+        out
+    }
+}
+
+fn json_range(span: &Span) -> String {
+    let (line, column) = span.line_col();
+    let (end_line, end_column) = span.end_line_col();
+    format!(
+        "{{\"start\":{{\"line\":{},\"column\":{}}},\"end\":{{\"line\":{},\"column\":{}}}}}",
+        line, column, end_line, end_column
+    )
+}
+
+fn json_option(text: Option<&str>) -> String {
+    text.map_or_else(|| "null".to_string(), json_string)
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(false))
+    }
+}
+
+/// The span's width on its first line, at least one column.
+fn underline_width(span: &Span) -> usize {
+    span.text()
+        .lines()
+        .next()
+        .map_or(0, |line| line.chars().count())
+        .max(1)
+}
+
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const RESET: &str = "\x1b[0m";
+
+struct Style {
+    color: bool,
+}
+
+impl Style {
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", code, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+
+/// A group of intrinsics that an evaluator may be allowed to use.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Capability {
+    /// Arithmetic, comparisons, lists, `eval` and `call`.
+    Core,
+    Math,
+    Strings,
+    /// `print`, `read-line` and the like, which use the evaluator's own
+    /// streams and so stay under the host's control.
+    Console,
+    /// Reading, writing and deleting files.
+    Files,
+    /// Environment variables, the clock and command line arguments.
+    Os,
+    /// Running commands and exiting the process.
+    Process,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 7] = [
+        Capability::Core,
+        Capability::Math,
+        Capability::Strings,
+        Capability::Console,
+        Capability::Files,
+        Capability::Os,
+        Capability::Process,
+    ];
+
+    /// A shorter name that also qualifies the capability's intrinsics, as
+    /// in `str.split`.
+    pub(super) fn short_name(self) -> Option<&'static str> {
+        match self {
+            Capability::Strings => Some("str"),
+            _ => None,
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Capability::Core => "core",
+            Capability::Math => "math",
+            Capability::Strings => "strings",
+            Capability::Console => "console",
+            Capability::Files => "files",
+            Capability::Os => "os",
+            Capability::Process => "process",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The set of capabilities an evaluator is created with. Intrinsics of a
+/// missing capability stay known by name but fail with
+/// `EvalError::PermissionDenied` when called.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Capabilities {
+    bits: u8,
+}
+
+impl Capabilities {
+    pub fn none() -> Self {
+        Self { bits: 0 }
+    }
+
+    pub fn all() -> Self {
+        Capability::ALL
+            .into_iter()
+            .fold(Self::none(), Capabilities::with)
+    }
+
+    /// Pure computation and console output, for running untrusted code.
+    pub fn sandbox() -> Self {
+        Self::none()
+            .with(Capability::Core)
+            .with(Capability::Math)
+            .with(Capability::Strings)
+            .with(Capability::Console)
+    }
+
+    pub fn with(self, capability: Capability) -> Self {
+        Self {
+            bits: self.bits | capability.bit(),
+        }
+    }
+
+    pub fn without(self, capability: Capability) -> Self {
+        Self {
+            bits: self.bits & !capability.bit(),
+        }
+    }
+
+    pub fn allows(&self, capability: Capability) -> bool {
+        self.bits & capability.bit() != 0
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::all()
+    }
+}
@@ -0,0 +1,223 @@
+use std::rc::Rc;
+
+use crate::values::{Env, List, OwlError, Symbol, Value};
+
+use super::{convert_arg, type_error, Arity, EvalError, EvalResult, Evaluator, Intrinsic};
+
+pub(super) fn register(evaluator: &mut Evaluator) {
+    evaluator.add_intrinsic(Throw {});
+    evaluator.add_intrinsic(MakeError {});
+    evaluator.register_fn("error?", |v: Value| matches!(v, Value::Error(_)));
+    evaluator.register_fn("error-kind", |e: Rc<OwlError>| Value::Atom(e.kind().into()));
+    evaluator.register_fn("error-message", |e: Rc<OwlError>| e.message().to_string());
+    evaluator.register_fn("error-payload", |e: Rc<OwlError>| e.payload().clone());
+}
+
+impl EvalError {
+    /// The kind of error values made from this error, such as `type`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EvalError::Reader(_) => "reader",
+            EvalError::Arity { .. } => "arity",
+            EvalError::Type { .. } => "type",
+            EvalError::ReturnType { .. } => "return-type",
+            EvalError::Unbound { .. } => "unbound",
+            EvalError::NotCallable(_) => "not-callable",
+            EvalError::NoMethod { .. } => "no-method",
+            EvalError::Native(_) => "native",
+            EvalError::Syntax(_) => "syntax",
+            EvalError::Arithmetic { .. } => "arithmetic",
+            EvalError::Io(_) => "io",
+            EvalError::OutOfFuel => "out-of-fuel",
+            EvalError::DepthExceeded(_) => "depth-exceeded",
+            EvalError::Interrupted => "interrupted",
+            EvalError::OutOfMemory(_) => "out-of-memory",
+            EvalError::PermissionDenied { .. } => "permission-denied",
+            EvalError::ModuleNotFound(_) => "module-not-found",
+            EvalError::ImportCycle(_) => "import-cycle",
+            EvalError::Thrown(_) => "thrown",
+        }
+    }
+
+    /// A code identifying the kind of error that stays the same across
+    /// releases. Reader errors keep their own codes.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EvalError::Reader(e) => e.code(),
+            EvalError::Arity { .. } => "E0001",
+            EvalError::Type { .. } => "E0002",
+            EvalError::ReturnType { .. } => "E0003",
+            EvalError::Unbound { .. } => "E0004",
+            EvalError::NotCallable(_) => "E0005",
+            EvalError::NoMethod { .. } => "E0006",
+            EvalError::Native(_) => "E0007",
+            EvalError::Syntax(_) => "E0008",
+            EvalError::Io(_) => "E0009",
+            EvalError::OutOfFuel => "E0010",
+            EvalError::DepthExceeded(_) => "E0011",
+            EvalError::Interrupted => "E0012",
+            EvalError::OutOfMemory(_) => "E0013",
+            EvalError::PermissionDenied { .. } => "E0014",
+            EvalError::ModuleNotFound(_) => "E0015",
+            EvalError::ImportCycle(_) => "E0016",
+            EvalError::Thrown(_) => "E0017",
+            EvalError::Arithmetic { .. } => "E0018",
+        }
+    }
+
+    /// Whether `try` may catch this error. Running out of a limit set by
+    /// the host is not, so scripts cannot escape it.
+    pub fn is_catchable(&self) -> bool {
+        !matches!(
+            self,
+            EvalError::OutOfFuel
+                | EvalError::DepthExceeded(_)
+                | EvalError::Interrupted
+                | EvalError::OutOfMemory(_)
+        )
+    }
+
+    /// The error value `try` binds for this error. Thrown errors are
+    /// passed through unchanged and other thrown values become the payload
+    /// of a `thrown` error.
+    pub fn to_value(&self) -> Value {
+        let payload = match self {
+            EvalError::Thrown(Value::Error(e)) => return Value::Error(e.clone()),
+            EvalError::Thrown(value) => value.clone(),
+            EvalError::Arity { name, .. }
+            | EvalError::Type { name, .. }
+            | EvalError::ReturnType { name, .. }
+            | EvalError::PermissionDenied { name, .. }
+            | EvalError::Arithmetic { name, .. }
+            | EvalError::Unbound { name, .. }
+            | EvalError::ModuleNotFound(name) => Value::Str(name.clone()),
+            EvalError::NoMethod { method, .. } => Value::Str(method.clone()),
+            EvalError::ImportCycle(files) => {
+                Value::list(files.iter().cloned().map(Value::Str).collect())
+            }
+            _ => Value::None,
+        };
+        let message = match self {
+            EvalError::Thrown(value) => value.to_string(),
+            e => e.to_string(),
+        };
+        Value::Error(Rc::new(OwlError::new(self.kind(), message, payload)))
+    }
+}
+
+impl Evaluator {
+    /// `(try body... (catch e handler...) (finally cleanup...))` evaluates
+    /// the body and, if it fails, the handler with `e` bound to the error
+    /// value. The cleanup runs however the body and handler exit, and its
+    /// own failure takes precedence, except when they run out of a limit
+    /// set by the host: those errors leave at once, so a script cannot
+    /// keep running after an interrupt. Either clause may be left out.
+    pub(super) fn evaluate_try(&self, env: &Env, args: &List) -> EvalResult {
+        let catch = Symbol::new("catch");
+        let finally = Symbol::new("finally");
+        let clause = |form: &Value, name: Symbol| match form {
+            Value::List(xs) if xs.first() == Some(&Value::Sym(name)) => Some(xs.rest()),
+            _ => None,
+        };
+
+        let forms = args.to_vec();
+        let mut end = forms.len();
+        let cleanup = match forms.last().and_then(|f| clause(f, finally)) {
+            Some(cleanup) => {
+                end -= 1;
+                Some(cleanup)
+            }
+            None => None,
+        };
+        let handler = match forms[..end].last().and_then(|f| clause(f, catch)) {
+            Some(handler) => {
+                end -= 1;
+                let var = match handler.first() {
+                    Some(Value::Sym(var)) => *var,
+                    _ => {
+                        return Err(EvalError::Syntax(format!(
+                            "Expected a name in {}",
+                            forms[end]
+                        )))
+                    }
+                };
+                Some((var, handler.rest()))
+            }
+            None => None,
+        };
+        if let Some(misplaced) = forms[..end]
+            .iter()
+            .find(|f| clause(f, catch).is_some() || clause(f, finally).is_some())
+        {
+            return Err(EvalError::Syntax(format!(
+                "Misplaced clause {} in try",
+                misplaced
+            )));
+        }
+
+        let mut result = self.evaluate_body(env, &forms[..end]);
+        if let (Err(e), Some((var, body))) = (&result, &handler) {
+            if e.is_catchable() {
+                self.clear_backtrace();
+                let scope = env.child();
+                scope.set(*var, e.to_value());
+                result = self.evaluate_body(&scope, &body.to_vec());
+            }
+        }
+        if result.as_ref().is_err_and(|e| !e.is_catchable()) {
+            return result;
+        }
+        if let Some(cleanup) = cleanup {
+            self.evaluate_body(env, &cleanup.to_vec())?;
+        }
+        result
+    }
+
+    fn evaluate_body(&self, env: &Env, forms: &[Value]) -> EvalResult {
+        let mut result = Value::None;
+        for form in forms {
+            result = self.evaluate(env, form)?;
+        }
+        Ok(result)
+    }
+}
+
+/// `(throw value)` raises `value`, usually an error value, to the nearest
+/// `try`.
+struct Throw;
+impl Intrinsic for Throw {
+    fn name(&self) -> &'static str {
+        "throw"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        Err(EvalError::Thrown(args[0].clone()))
+    }
+}
+
+/// `(error kind message)` or `(error kind message payload)` makes an error
+/// value. The kind is an atom such as `:parse`.
+struct MakeError;
+impl Intrinsic for MakeError {
+    fn name(&self) -> &'static str {
+        "error"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::between(2, 3)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let kind = match &args[0] {
+            Value::Atom(kind) => kind.clone(),
+            v => return Err(type_error(self.name(), 0, "atom", v)),
+        };
+        let message = convert_arg::<String>(self.name(), 1, &args[1])?;
+        let payload = args.get(2).cloned().unwrap_or(Value::None);
+        Ok(Value::Error(Rc::new(OwlError::new(kind, message, payload))))
+    }
+}
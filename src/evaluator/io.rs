@@ -0,0 +1,284 @@
+use std::{
+    cell::RefCell,
+    fs::{self, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    rc::Rc,
+};
+
+use crate::values::{Env, Value};
+
+use super::{convert_arg, Arity, EvalError, EvalResult, Evaluator, Intrinsic};
+
+pub(super) fn register_console(evaluator: &mut Evaluator) {
+    evaluator.add_intrinsic(Print {
+        name: "print",
+        newline: false,
+        stderr: false,
+    });
+    evaluator.add_intrinsic(Print {
+        name: "println",
+        newline: true,
+        stderr: false,
+    });
+    evaluator.add_intrinsic(Print {
+        name: "eprint",
+        newline: false,
+        stderr: true,
+    });
+    evaluator.add_intrinsic(Print {
+        name: "eprintln",
+        newline: true,
+        stderr: true,
+    });
+    evaluator.add_intrinsic(ReadLine {});
+}
+
+pub(super) fn register_files(evaluator: &mut Evaluator) {
+    evaluator.add_intrinsic(ReadFile {});
+    evaluator.add_intrinsic(WriteFile {
+        name: "write-file",
+        append: false,
+    });
+    evaluator.add_intrinsic(WriteFile {
+        name: "append-file",
+        append: true,
+    });
+    evaluator.register_fn("file-exists?", |path: String| Path::new(&path).exists());
+    evaluator.add_intrinsic(DeleteFile {});
+    evaluator.add_intrinsic(ReadLines {});
+    evaluator.add_intrinsic(EachLine {});
+}
+
+/// An in-memory sink for captured output. Clones share the same buffer,
+/// so the host keeps one while the evaluator writes to another.
+#[derive(Clone, Default)]
+pub struct OutputBuffer {
+    bytes: Rc<RefCell<Vec<u8>>>,
+}
+
+impl OutputBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything written so far.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.bytes.borrow()).into_owned()
+    }
+
+    /// Returns everything written so far and empties the buffer.
+    pub fn take(&self) -> String {
+        let bytes = std::mem::take(&mut *self.bytes.borrow_mut());
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+impl Write for OutputBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reports an I/O failure of intrinsic `name` on `path`.
+fn io_error(name: &str, path: &str, error: io::Error) -> EvalError {
+    EvalError::Io(format!("{}: {}: {}", name, path, error))
+}
+
+/// Checks that the file at `path` fits within the memory limit before it
+/// is read whole.
+fn reserve_file(evaluator: &Evaluator, name: &str, path: &str) -> Result<(), EvalError> {
+    let len = fs::metadata(path)
+        .map_err(|e| io_error(name, path, e))?
+        .len();
+    evaluator.reserve(usize::try_from(len).unwrap_or(usize::MAX))
+}
+
+/// Writes its arguments separated by spaces. Strings are written without
+/// quotes.
+struct Print {
+    name: &'static str,
+    newline: bool,
+    stderr: bool,
+}
+
+impl Intrinsic for Print {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let mut text = args
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        if self.newline {
+            text.push('\n');
+        }
+        let stream = if self.stderr {
+            &evaluator.error_output
+        } else {
+            &evaluator.output
+        };
+        let mut stream = stream
+            .try_borrow_mut()
+            .map_err(|_| EvalError::Io(format!("{}: output is already in use", self.name)))?;
+        stream
+            .write_all(text.as_bytes())
+            .and_then(|_| stream.flush())
+            .map_err(|e| EvalError::Io(format!("{}: {}", self.name, e)))?;
+        Ok(Value::None)
+    }
+}
+
+/// The next line of input without its line ending, or none at the end.
+struct ReadLine;
+impl Intrinsic for ReadLine {
+    fn name(&self) -> &'static str {
+        "read-line"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(0)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, _args: &[Value]) -> EvalResult {
+        let mut input = evaluator
+            .input
+            .try_borrow_mut()
+            .map_err(|_| EvalError::Io(format!("{}: input is already in use", self.name())))?;
+        let mut line = String::new();
+        match input.read_line(&mut line) {
+            Ok(0) => Ok(Value::None),
+            Ok(_) => Ok(Value::Str(trim_line_ending(line))),
+            Err(e) => Err(EvalError::Io(format!("{}: {}", self.name(), e))),
+        }
+    }
+}
+
+fn trim_line_ending(mut line: String) -> String {
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    line
+}
+
+struct ReadFile;
+impl Intrinsic for ReadFile {
+    fn name(&self) -> &'static str {
+        "read-file"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let path = convert_arg::<String>(self.name(), 0, &args[0])?;
+        reserve_file(evaluator, self.name(), &path)?;
+        fs::read_to_string(&path)
+            .map(Value::Str)
+            .map_err(|e| io_error(self.name(), &path, e))
+    }
+}
+
+/// `(write-file path text)` replaces the file, `append-file` adds to its
+/// end. Both create the file if needed.
+struct WriteFile {
+    name: &'static str,
+    append: bool,
+}
+
+impl Intrinsic for WriteFile {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(2)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let path = convert_arg::<String>(self.name, 0, &args[0])?;
+        let text = convert_arg::<String>(self.name, 1, &args[1])?;
+        OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(self.append)
+            .truncate(!self.append)
+            .open(&path)
+            .and_then(|mut file| file.write_all(text.as_bytes()))
+            .map_err(|e| io_error(self.name, &path, e))?;
+        Ok(Value::None)
+    }
+}
+
+struct DeleteFile;
+impl Intrinsic for DeleteFile {
+    fn name(&self) -> &'static str {
+        "delete-file"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let path = convert_arg::<String>(self.name(), 0, &args[0])?;
+        fs::remove_file(&path).map_err(|e| io_error(self.name(), &path, e))?;
+        Ok(Value::None)
+    }
+}
+
+/// All lines of a file as a list of strings.
+struct ReadLines;
+impl Intrinsic for ReadLines {
+    fn name(&self) -> &'static str {
+        "read-lines"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let path = convert_arg::<String>(self.name(), 0, &args[0])?;
+        reserve_file(evaluator, self.name(), &path)?;
+        let text = fs::read_to_string(&path).map_err(|e| io_error(self.name(), &path, e))?;
+        Ok(Value::list(
+            text.lines().map(|line| Value::Str(line.into())).collect(),
+        ))
+    }
+}
+
+/// `(each-line path f)` calls `f` on each line as it is read, so large
+/// files are never held in memory at once.
+struct EachLine;
+impl Intrinsic for EachLine {
+    fn name(&self) -> &'static str {
+        "each-line"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(2)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        let path = convert_arg::<String>(self.name(), 0, &args[0])?;
+        let file = fs::File::open(&path).map_err(|e| io_error(self.name(), &path, e))?;
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| io_error(self.name(), &path, e))?;
+            evaluator.apply_in(env, &args[1], &[Value::Str(line)])?;
+        }
+        Ok(Value::None)
+    }
+}
@@ -0,0 +1,23 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Stops a running evaluation from another thread or a timer. The
+/// evaluator notices at its next step and fails with
+/// `EvalError::Interrupted`, after which the handle is ready to use again.
+#[derive(Debug, Clone, Default)]
+pub struct InterruptHandle {
+    flag: Arc<AtomicBool>,
+}
+
+impl InterruptHandle {
+    pub fn interrupt(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether an interrupt is pending, clearing it.
+    pub(super) fn take(&self) -> bool {
+        self.flag.load(Ordering::Relaxed) && self.flag.swap(false, Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,436 @@
+use std::cmp::Ordering;
+
+use crate::values::{gc, Env, List, Value};
+
+use super::{convert_arg, Arity, EvalError, EvalResult, Evaluator, Intrinsic};
+
+pub(super) fn register(evaluator: &mut Evaluator) {
+    evaluator.add_intrinsic(MakeList {});
+    evaluator.register_fn("cons", List::cons);
+    evaluator.register_fn("car", |xs: List| xs.car().cloned());
+    evaluator.register_fn("cdr", |xs: List| xs.cdr().cloned());
+    evaluator.register_fn("first", |xs: List| xs.first().cloned());
+    evaluator.register_fn("rest", |xs: List| xs.rest());
+    evaluator.register_fn("nth", |xs: List, n: i64| {
+        usize::try_from(n).ok().and_then(|n| xs.get(n).cloned())
+    });
+    evaluator.register_fn("length", |xs: List| xs.len() as i64);
+    evaluator.register_fn("reverse", |xs: List| {
+        xs.iter()
+            .fold(List::new(), |reversed, x| reversed.prepend(x.clone()))
+    });
+    evaluator.register_fn("take", |n: i64, xs: List| {
+        xs.iter().take(n.max(0) as usize).cloned().collect::<List>()
+    });
+    evaluator.register_fn("drop", |n: i64, mut xs: List| {
+        for _ in 0..n {
+            if xs.is_empty() {
+                break;
+            }
+            xs = xs.rest();
+        }
+        xs
+    });
+    evaluator.add_intrinsic(Append {});
+    evaluator.add_intrinsic(Range {});
+    evaluator.add_intrinsic(Zip {});
+    evaluator.add_intrinsic(Map {});
+    evaluator.add_intrinsic(Filter {});
+    evaluator.add_intrinsic(Reduce {});
+    evaluator.add_intrinsic(Fold {});
+    evaluator.add_intrinsic(Any {});
+    evaluator.add_intrinsic(All {});
+    evaluator.add_intrinsic(Find {});
+    evaluator.add_intrinsic(Sort {});
+    evaluator.add_intrinsic(GroupBy {});
+}
+
+fn list_arg(name: &str, position: usize, value: &Value) -> Result<List, EvalError> {
+    convert_arg::<List>(name, position, value)
+}
+
+/// Calls `func` on `x` and returns whether the result is true.
+fn test(evaluator: &Evaluator, env: &Env, func: &Value, x: &Value) -> Result<bool, EvalError> {
+    Ok(evaluator
+        .apply_in(env, func, std::slice::from_ref(x))?
+        .is_true())
+}
+
+struct MakeList;
+impl Intrinsic for MakeList {
+    fn name(&self) -> &'static str {
+        "list"
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        Ok(Value::List(args.iter().cloned().collect()))
+    }
+}
+
+/// The result shares structure with the last list.
+struct Append;
+impl Intrinsic for Append {
+    fn name(&self) -> &'static str {
+        "append"
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let mut lists = args
+            .iter()
+            .enumerate()
+            .map(|(i, v)| list_arg(self.name(), i, v))
+            .collect::<Result<Vec<_>, _>>()?;
+        evaluator.reserve_cells(
+            lists
+                .iter()
+                .take(lists.len().saturating_sub(1))
+                .map(List::len)
+                .sum(),
+        )?;
+        let mut result = lists.pop().unwrap_or_default();
+        for xs in lists.iter().rev() {
+            for x in xs.to_vec().into_iter().rev() {
+                result = result.prepend(x);
+            }
+        }
+        Ok(Value::List(result))
+    }
+}
+
+/// `(range end)`, `(range start end)` or `(range start end step)`; `end` is
+/// exclusive.
+struct Range;
+impl Intrinsic for Range {
+    fn name(&self) -> &'static str {
+        "range"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::between(1, 3)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let nums = args
+            .iter()
+            .enumerate()
+            .map(|(i, v)| convert_arg::<f64>(self.name(), i, v))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (start, end, step) = match nums[..] {
+            [end] => (0.0, end, 1.0),
+            [start, end] => (start, end, 1.0),
+            [start, end, step] => (start, end, step),
+            _ => unreachable!(),
+        };
+        if let Some(n) = nums.iter().find(|n| !n.is_finite()) {
+            return Err(EvalError::Native(format!("range: {} is not finite", n)));
+        }
+        if step == 0.0 {
+            return Err(EvalError::Native("range: step must not be zero".into()));
+        }
+        // Checked before allocating, as the cast below saturates.
+        let len = ((end - start) / step).ceil().max(0.0);
+        if len > (isize::MAX as usize / gc::CELL_BYTES) as f64 {
+            return Err(EvalError::Native(format!(
+                "range: {} elements is too many",
+                len
+            )));
+        }
+        let len = len as usize;
+        evaluator.reserve_cells(len)?;
+        Ok(Value::list(
+            (0..len)
+                .map(|i| Value::Num(start + i as f64 * step))
+                .collect(),
+        ))
+    }
+}
+
+/// Lists of corresponding elements, as long as the shortest list.
+struct Zip;
+impl Intrinsic for Zip {
+    fn name(&self) -> &'static str {
+        "zip"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::at_least(1)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let lists = args
+            .iter()
+            .enumerate()
+            .map(|(i, v)| list_arg(self.name(), i, v))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut iters = lists.iter().map(List::iter).collect::<Vec<_>>();
+        let mut result = Vec::new();
+        while let Some(row) = iters
+            .iter_mut()
+            .map(|it| it.next().cloned())
+            .collect::<Option<Vec<_>>>()
+        {
+            result.push(Value::list(row));
+        }
+        Ok(Value::list(result))
+    }
+}
+
+/// `(map f xs ...)` calls `f` with one element from each list, stopping at
+/// the end of the shortest.
+struct Map;
+impl Intrinsic for Map {
+    fn name(&self) -> &'static str {
+        "map"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::at_least(2)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        let lists = args[1..]
+            .iter()
+            .enumerate()
+            .map(|(i, v)| list_arg(self.name(), i + 1, v))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut iters = lists.iter().map(List::iter).collect::<Vec<_>>();
+        let mut result = Vec::new();
+        while let Some(row) = iters
+            .iter_mut()
+            .map(|it| it.next().cloned())
+            .collect::<Option<Vec<_>>>()
+        {
+            result.push(evaluator.apply_in(env, &args[0], &row)?);
+        }
+        Ok(Value::list(result))
+    }
+}
+
+struct Filter;
+impl Intrinsic for Filter {
+    fn name(&self) -> &'static str {
+        "filter"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(2)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        let mut result = Vec::new();
+        for x in &list_arg(self.name(), 1, &args[1])? {
+            if test(evaluator, env, &args[0], x)? {
+                result.push(x.clone());
+            }
+        }
+        Ok(Value::list(result))
+    }
+}
+
+fn fold(evaluator: &Evaluator, env: &Env, func: &Value, init: Value, xs: &List) -> EvalResult {
+    xs.iter().try_fold(init, |acc, x| {
+        evaluator.apply_in(env, func, &[acc, x.clone()])
+    })
+}
+
+/// `(reduce f xs)` folds from the left starting with the first element,
+/// `(reduce f init xs)` starts with `init`.
+struct Reduce;
+impl Intrinsic for Reduce {
+    fn name(&self) -> &'static str {
+        "reduce"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::between(2, 3)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        if let [func, init, xs] = args {
+            let xs = list_arg(self.name(), 2, xs)?;
+            return fold(evaluator, env, func, init.clone(), &xs);
+        }
+        let xs = list_arg(self.name(), 1, &args[1])?;
+        match xs.first() {
+            Some(init) => fold(evaluator, env, &args[0], init.clone(), &xs.rest()),
+            None => Err(EvalError::Native(
+                "reduce: empty list with no initial value".into(),
+            )),
+        }
+    }
+}
+
+/// `(fold f init xs)`, calling `(f acc x)` for each element.
+struct Fold;
+impl Intrinsic for Fold {
+    fn name(&self) -> &'static str {
+        "fold"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(3)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        let xs = list_arg(self.name(), 2, &args[2])?;
+        fold(evaluator, env, &args[0], args[1].clone(), &xs)
+    }
+}
+
+struct Any;
+impl Intrinsic for Any {
+    fn name(&self) -> &'static str {
+        "any?"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(2)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        for x in &list_arg(self.name(), 1, &args[1])? {
+            if test(evaluator, env, &args[0], x)? {
+                return Ok(Value::Bool(true));
+            }
+        }
+        Ok(Value::Bool(false))
+    }
+}
+
+struct All;
+impl Intrinsic for All {
+    fn name(&self) -> &'static str {
+        "all?"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(2)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        for x in &list_arg(self.name(), 1, &args[1])? {
+            if !test(evaluator, env, &args[0], x)? {
+                return Ok(Value::Bool(false));
+            }
+        }
+        Ok(Value::Bool(true))
+    }
+}
+
+/// The first element satisfying the predicate, or none.
+struct Find;
+impl Intrinsic for Find {
+    fn name(&self) -> &'static str {
+        "find"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(2)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        for x in &list_arg(self.name(), 1, &args[1])? {
+            if test(evaluator, env, &args[0], x)? {
+                return Ok(x.clone());
+            }
+        }
+        Ok(Value::None)
+    }
+}
+
+/// `(sort xs)` orders numbers or strings ascending; `(sort xs less?)` uses
+/// a function returning true when its first argument goes first. The sort
+/// is stable.
+struct Sort;
+impl Sort {
+    fn compare(&self, a: &Value, b: &Value) -> Result<Ordering, EvalError> {
+        match (a, b) {
+            (Value::Num(a), Value::Num(b)) => Ok(a.total_cmp(b)),
+            (Value::Str(a), Value::Str(b)) | (Value::Atom(a), Value::Atom(b)) => Ok(a.cmp(b)),
+            _ => Err(EvalError::Native(format!(
+                "sort: cannot compare {} with {}",
+                a.type_name(),
+                b.type_name()
+            ))),
+        }
+    }
+}
+
+impl Intrinsic for Sort {
+    fn name(&self) -> &'static str {
+        "sort"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::between(1, 2)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        let xs = list_arg(self.name(), 0, &args[0])?.to_vec();
+        let sorted = merge_sort(xs, &mut |a, b| match args.get(1) {
+            Some(less) => Ok(evaluator
+                .apply_in(env, less, &[b.clone(), a.clone()])?
+                .is_true()),
+            None => Ok(self.compare(a, b)? == Ordering::Greater),
+        })?;
+        Ok(Value::list(sorted))
+    }
+}
+
+/// A stable merge sort with a fallible comparison, which `slice::sort_by`
+/// cannot provide. `after(a, b)` is true when `a` must come after `b`.
+fn merge_sort<F>(mut xs: Vec<Value>, after: &mut F) -> Result<Vec<Value>, EvalError>
+where
+    F: FnMut(&Value, &Value) -> Result<bool, EvalError>,
+{
+    if xs.len() <= 1 {
+        return Ok(xs);
+    }
+    let right = xs.split_off(xs.len() / 2);
+    let left = merge_sort(xs, after)?;
+    let right = merge_sort(right, after)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        if after(a, b)? {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+/// `(group-by f xs)` returns an association list of `(key items)` pairs,
+/// in the order each key first appears.
+struct GroupBy;
+impl Intrinsic for GroupBy {
+    fn name(&self) -> &'static str {
+        "group-by"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(2)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        let mut groups: Vec<(Value, Vec<Value>)> = Vec::new();
+        for x in &list_arg(self.name(), 1, &args[1])? {
+            let key = evaluator.apply_in(env, &args[0], std::slice::from_ref(x))?;
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, items)) => items.push(x.clone()),
+                None => groups.push((key, vec![x.clone()])),
+            }
+        }
+        Ok(Value::list(
+            groups
+                .into_iter()
+                .map(|(key, items)| Value::list(vec![key, Value::list(items)]))
+                .collect(),
+        ))
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    f64::consts,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::values::{Env, List, Value};
+
+use super::{convert_arg, Arity, EvalError, EvalResult, Evaluator, Intrinsic};
+
+pub(super) fn register(evaluator: &mut Evaluator) {
+    evaluator.define_constant("pi", Value::Num(consts::PI));
+    evaluator.define_constant("e", Value::Num(consts::E));
+
+    // `mod` takes the sign of the divisor, `rem` that of the dividend.
+    evaluator.add_intrinsic(IntegerDivision {
+        name: "quot",
+        op: |a, b| (a / b).trunc(),
+    });
+    evaluator.add_intrinsic(IntegerDivision {
+        name: "mod",
+        op: |a, b| a - b * (a / b).floor(),
+    });
+    evaluator.add_intrinsic(IntegerDivision {
+        name: "rem",
+        op: |a, b| a % b,
+    });
+    evaluator.register_fn("abs", f64::abs);
+    evaluator.register_fn("floor", f64::floor);
+    evaluator.register_fn("ceil", f64::ceil);
+    evaluator.register_fn("round", f64::round);
+    evaluator.register_fn("trunc", f64::trunc);
+    evaluator.register_fn("sqrt", f64::sqrt);
+    evaluator.register_fn("pow", f64::powf);
+    evaluator.register_fn("exp", f64::exp);
+    evaluator.add_intrinsic(Log {});
+    evaluator.register_fn("sin", f64::sin);
+    evaluator.register_fn("cos", f64::cos);
+    evaluator.register_fn("tan", f64::tan);
+    evaluator.register_fn("atan2", f64::atan2);
+    evaluator.add_intrinsic(Extremum {
+        name: "min",
+        pick: f64::min,
+    });
+    evaluator.add_intrinsic(Extremum {
+        name: "max",
+        pick: f64::max,
+    });
+    evaluator.register_fn("clamp", |x: f64, lo: f64, hi: f64| x.max(lo).min(hi));
+    evaluator.register_fn("lerp", |a: f64, b: f64, t: f64| a + (b - a) * t);
+
+    evaluator.add_intrinsic(Random {});
+    evaluator.add_intrinsic(RandomInt {});
+    evaluator.add_intrinsic(RandomSeed {});
+    evaluator.add_intrinsic(Shuffle {});
+    evaluator.add_intrinsic(Choose {});
+}
+
+/// SplitMix64, a small generator whose whole state is one word. It is
+/// fast and repeatable, but not suitable for cryptography.
+#[derive(Clone, Copy)]
+pub(super) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self::new(nanos)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `[0, n)`.
+    fn below(&mut self, n: u64) -> u64 {
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+}
+
+/// `(log x)` is the natural logarithm, `(log x base)` uses `base`.
+struct Log;
+impl Intrinsic for Log {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::between(1, 2)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let x = convert_arg::<f64>(self.name(), 0, &args[0])?;
+        match args.get(1) {
+            Some(base) => Ok(Value::Num(x.log(convert_arg(self.name(), 1, base)?))),
+            None => Ok(Value::Num(x.ln())),
+        }
+    }
+}
+
+/// Division rounding towards an integer, which unlike `/` fails when
+/// dividing by zero.
+struct IntegerDivision {
+    name: &'static str,
+    op: fn(f64, f64) -> f64,
+}
+
+impl Intrinsic for IntegerDivision {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(2)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let a = convert_arg::<f64>(self.name, 0, &args[0])?;
+        let b = convert_arg::<f64>(self.name, 1, &args[1])?;
+        if b == 0.0 {
+            return Err(EvalError::Arithmetic {
+                name: self.name.into(),
+                message: "division by zero".into(),
+            });
+        }
+        Ok(Value::Num((self.op)(a, b)))
+    }
+}
+
+struct Extremum {
+    name: &'static str,
+    pick: fn(f64, f64) -> f64,
+}
+
+impl Intrinsic for Extremum {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::at_least(1)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let mut result = convert_arg::<f64>(self.name, 0, &args[0])?;
+        for (i, arg) in args.iter().enumerate().skip(1) {
+            result = (self.pick)(result, convert_arg(self.name, i, arg)?);
+        }
+        Ok(Value::Num(result))
+    }
+}
+
+/// A number in `[0, 1)`.
+struct Random;
+impl Intrinsic for Random {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(0)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, _args: &[Value]) -> EvalResult {
+        Ok(Value::Num(evaluator.with_rng(Rng::next_f64)))
+    }
+}
+
+/// `(random-int n)` is in `[0, n)`, `(random-int lo hi)` in `[lo, hi)`.
+struct RandomInt;
+impl Intrinsic for RandomInt {
+    fn name(&self) -> &'static str {
+        "random-int"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::between(1, 2)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let bounds = args
+            .iter()
+            .enumerate()
+            .map(|(i, v)| convert_arg::<i64>(self.name(), i, v))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (lo, hi) = match bounds[..] {
+            [hi] => (0, hi),
+            [lo, hi] => (lo, hi),
+            _ => unreachable!(),
+        };
+        if lo >= hi {
+            return Err(EvalError::Native(format!(
+                "random-int: empty range {} to {}",
+                lo, hi
+            )));
+        }
+        let offset = evaluator.with_rng(|rng| rng.below(hi.abs_diff(lo)));
+        Ok(Value::Num(lo.wrapping_add(offset as i64) as f64))
+    }
+}
+
+struct RandomSeed;
+impl Intrinsic for RandomSeed {
+    fn name(&self) -> &'static str {
+        "random-seed"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let seed = convert_arg::<i64>(self.name(), 0, &args[0])?;
+        evaluator.seed_random(seed as u64);
+        Ok(Value::None)
+    }
+}
+
+/// A new list with the elements in random order.
+struct Shuffle;
+impl Intrinsic for Shuffle {
+    fn name(&self) -> &'static str {
+        "shuffle"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let mut xs = convert_arg::<List>(self.name(), 0, &args[0])?.to_vec();
+        evaluator.with_rng(|rng| {
+            for i in (1..xs.len()).rev() {
+                xs.swap(i, rng.below(i as u64 + 1) as usize);
+            }
+        });
+        Ok(Value::list(xs))
+    }
+}
+
+/// A random element, or none for the empty list.
+struct Choose;
+impl Intrinsic for Choose {
+    fn name(&self) -> &'static str {
+        "choose"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let xs = convert_arg::<List>(self.name(), 0, &args[0])?;
+        match xs.len() {
+            0 => Ok(Value::None),
+            n => {
+                let i = evaluator.with_rng(|rng| rng.below(n as u64));
+                Ok(xs.get(i as usize).cloned().unwrap_or(Value::None))
+            }
+        }
+    }
+}
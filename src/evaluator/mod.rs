@@ -1,290 +1,1141 @@
-use core::panic;
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::fmt::Pointer;
+use std::fmt;
+use std::io::{self as stdio, BufRead, BufReader, Write};
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::rc::Rc;
 
-use crate::reader::Reader;
-use crate::values::Value::{Atom, Bool, Func, List, Num, Str, Sym};
-use crate::values::{car, cdr, Env, Value};
+use crate::reader::{Reader, ReaderError};
+use crate::values::gc::{self, MemoryStats};
+use crate::values::Value::{Atom, Bool, Func, Native, Num, Str, Sym};
+use crate::values::{Env, FromValue, Handle, List, OwlFunc, Source, Symbol, SymbolMap, Value};
 
-pub trait Intrinsic {
-    fn name(self: &Self) -> &'static str;
-    fn eval(self: &Self, evaluator: &Evaluator, env: &mut Env, args: Value) -> Value;
+mod capabilities;
+mod exceptions;
+mod io;
+mod limits;
+mod lists;
+mod math;
+mod modules;
+mod native;
+mod os;
+mod strings;
+mod suggest;
+mod trace;
+
+pub use capabilities::{Capabilities, Capability};
+pub use io::OutputBuffer;
+pub use limits::InterruptHandle;
+pub use native::{convert_arg, IntoArgs, NativeFn};
+pub use trace::{Backtrace, Frame};
+
+/// The memory limit of new evaluators, in bytes.
+pub const DEFAULT_MEMORY_LIMIT: usize = 1 << 30;
+
+/// The nesting limit of new evaluators. Unoptimised builds overflow an
+/// 8 MB stack at about 1500 nested forms, or 390 calls of a recursive
+/// function, and optimised builds at about 6000.
+pub const DEFAULT_MAX_DEPTH: usize = 1000;
+
+/// How many arguments an intrinsic accepts. `max` of `None` means variadic.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Arity {
+    pub min: usize,
+    pub max: Option<usize>,
 }
 
-struct Nop {}
-impl Intrinsic for Nop {
-    fn name(self: &Self) -> &'static str {
-        "nop"
+impl Arity {
+    pub fn any() -> Self {
+        Self { min: 0, max: None }
+    }
+
+    pub fn exactly(n: usize) -> Self {
+        Self {
+            min: n,
+            max: Some(n),
+        }
+    }
+
+    pub fn at_least(n: usize) -> Self {
+        Self { min: n, max: None }
     }
 
-    fn eval(self: &Self, evaluator: &Evaluator, env: &mut Env, args: Value) -> Value {
-        args
+    pub fn between(min: usize, max: usize) -> Self {
+        Self {
+            min,
+            max: Some(max),
+        }
+    }
+
+    pub fn accepts(&self, n: usize) -> bool {
+        n >= self.min && self.max.is_none_or(|max| n <= max)
     }
 }
 
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{}", self.min),
+            Some(max) => write!(f, "{} to {}", self.min, max),
+            None => write!(f, "at least {}", self.min),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum EvalError {
+    Reader(ReaderError),
+    Arity {
+        name: String,
+        expected: Arity,
+        got: usize,
+    },
+    Type {
+        name: String,
+        position: usize,
+        expected: String,
+        got: String,
+    },
+    ReturnType {
+        name: String,
+        expected: String,
+        got: String,
+    },
+    /// A name bound nowhere, with bound names spelled like it.
+    Unbound {
+        name: String,
+        suggestions: Vec<String>,
+    },
+    NotCallable(String),
+    NoMethod {
+        type_name: String,
+        method: String,
+    },
+    Native(String),
+    Syntax(String),
+    /// Integer division by zero, or a NaN or infinite result in checked
+    /// arithmetic.
+    Arithmetic {
+        name: String,
+        message: String,
+    },
+    /// A failed read or write, such as a missing file.
+    Io(String),
+    /// The step budget set with `Evaluator::set_fuel` ran out.
+    OutOfFuel,
+    /// Forms and calls nested deeper than `Evaluator::set_max_depth` allows.
+    DepthExceeded(usize),
+    /// The host triggered the evaluator's `InterruptHandle`.
+    Interrupted,
+    /// Scripts held more memory than `Evaluator::set_memory_limit` allows.
+    OutOfMemory(usize),
+    /// A call to an intrinsic whose capability the evaluator lacks.
+    PermissionDenied {
+        name: String,
+        capability: Capability,
+    },
+    /// An imported module that is neither defined nor found on disk.
+    ModuleNotFound(String),
+    /// The files of an import cycle, starting and ending with the same one.
+    ImportCycle(Vec<String>),
+    /// A value passed to `throw` that no `try` caught.
+    Thrown(Value),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Reader(e) => write!(f, "{}", e),
+            EvalError::Arity {
+                name,
+                expected,
+                got,
+            } => write!(f, "{} expects {} arguments but got {}", name, expected, got),
+            EvalError::Type {
+                name,
+                position,
+                expected,
+                got,
+            } => write!(
+                f,
+                "{}: argument {} expected {} but got {}",
+                name, position, expected, got
+            ),
+            EvalError::ReturnType {
+                name,
+                expected,
+                got,
+            } => write!(f, "{}: expected {} result but got {}", name, expected, got),
+            EvalError::Unbound { name, .. } => write!(f, "{} is not defined", name),
+            EvalError::NotCallable(got) => write!(f, "cannot call a value of type {}", got),
+            EvalError::NoMethod { type_name, method } => {
+                write!(f, "{} has no method {}", type_name, method)
+            }
+            EvalError::Native(msg) => write!(f, "{}", msg),
+            EvalError::Syntax(msg) => write!(f, "{}", msg),
+            EvalError::Arithmetic { name, message } => write!(f, "{}: {}", name, message),
+            EvalError::Io(msg) => write!(f, "{}", msg),
+            EvalError::OutOfFuel => write!(f, "evaluation ran out of fuel"),
+            EvalError::DepthExceeded(limit) => {
+                write!(f, "maximum nesting depth of {} exceeded", limit)
+            }
+            EvalError::Interrupted => write!(f, "evaluation was interrupted"),
+            EvalError::OutOfMemory(limit) => {
+                write!(f, "memory limit of {} bytes exceeded", limit)
+            }
+            EvalError::PermissionDenied { name, capability } => write!(
+                f,
+                "permission denied: {} needs the {} capability",
+                name, capability
+            ),
+            EvalError::ModuleNotFound(name) => write!(f, "module {} not found", name),
+            EvalError::ImportCycle(files) => write!(f, "import cycle: {}", files.join(" -> ")),
+            EvalError::Thrown(Value::Error(e)) => write!(f, "{}: {}", e.kind(), e.message()),
+            EvalError::Thrown(value) => write!(f, "uncaught exception: {}", value),
+        }
+    }
+}
+
+pub type EvalResult = Result<Value, EvalError>;
+
+pub trait Intrinsic {
+    fn name(&self) -> &'static str;
+
+    /// Checked by the evaluator before `eval` is called.
+    fn arity(&self) -> Arity {
+        Arity::any()
+    }
+
+    /// Raw intrinsics receive their argument forms unevaluated.
+    fn raw(&self) -> bool {
+        false
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult;
+}
+
 pub struct Evaluator {
-    intrinsics: HashMap<String, Box<dyn Intrinsic>>,
+    intrinsics: SymbolMap<Box<dyn Intrinsic>>,
+    capabilities: Capabilities,
+    /// Values of names such as `pi` that no environment binds.
+    constants: SymbolMap<Value>,
+    rng: Cell<math::Rng>,
+    /// Streams used by the I/O intrinsics, the process's own by default.
+    output: RefCell<Box<dyn Write>>,
+    error_output: RefCell<Box<dyn Write>>,
+    input: RefCell<Box<dyn BufRead>>,
+    /// Evaluation steps left, unlimited if `None`.
+    fuel: Cell<Option<u64>>,
+    max_depth: Cell<Option<usize>>,
+    depth: Cell<usize>,
+    interrupt: InterruptHandle,
+    memory_limit: Cell<Option<usize>>,
+    /// The memory held by values scripts created.
+    account: Rc<gc::Account>,
+    /// The capability group each built-in intrinsic belongs to.
+    groups: SymbolMap<Capability>,
+    /// Modules and namespaces by name, the module declared by each imported
+    /// file, and the aliases given with `import ... :as`.
+    namespaces: RefCell<SymbolMap<Rc<modules::Namespace>>>,
+    module_files: RefCell<HashMap<PathBuf, Symbol>>,
+    aliases: RefCell<SymbolMap<Symbol>>,
+    /// The namespace entered with `in-ns`, `None` for the global one.
+    namespace: Cell<Option<Symbol>>,
+    /// Files being evaluated, the innermost import last.
+    loading: RefCell<Vec<modules::Loading>>,
+    module_paths: Vec<PathBuf>,
+    /// The calls the last error unwound through.
+    trace: RefCell<Backtrace>,
+    trace_limit: Cell<usize>,
+    /// Whether unbound symbols evaluate to `none`.
+    lenient_lookup: Cell<bool>,
+    /// Whether intrinsics returning NaN or an infinity fail.
+    checked_arithmetic: Cell<bool>,
 }
 
 struct Eval;
 impl Intrinsic for Eval {
-    fn name(self: &Self) -> &'static str {
+    fn name(&self) -> &'static str {
         "eval"
     }
 
-    fn eval(self: &Self, evaluator: &Evaluator, env: &mut Env, args: Value) -> Value {
-        match args {
-            List(xs) => evaluator.eval(env, xs[0].clone()),
-            _ => Value::None,
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        match &args[0] {
+            Str(code) => evaluator.eval(env, code),
+            form => evaluator.evaluate(env, form),
         }
     }
 }
 
 struct Add;
 impl Intrinsic for Add {
-    fn name(self: &Self) -> &'static str {
+    fn name(&self) -> &'static str {
         "+"
     }
 
-    fn eval(self: &Self, evaluator: &Evaluator, env: &mut Env, args: Value) -> Value {
-        let mut total = 0.0;
-        match args {
-            List(args) => {
-                for value in args {
-                    total += match evaluator.evaluate(env, &value) {
-                        Num(n) => n,
-                        _ => 0.0,
-                    }
-                }
-            }
-            _ => {}
-        }
-        Num(total)
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        Ok(Num(numbers(self.name(), args)?.sum()))
     }
 }
 
 struct Mul;
 impl Intrinsic for Mul {
-    fn name(self: &Self) -> &'static str {
+    fn name(&self) -> &'static str {
         "*"
     }
 
-    fn eval(self: &Self, evaluator: &Evaluator, env: &mut Env, args: Value) -> Value {
-        let mut total = 1.0;
-        match args {
-            List(args) => {
-                for value in args {
-                    total *= match evaluator.evaluate(env, &value) {
-                        Num(n) => n,
-                        _ => 0.0,
-                    }
-                }
-            }
-            _ => {}
-        }
-        Num(total)
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        Ok(Num(numbers(self.name(), args)?.product()))
     }
 }
 
 struct Sub;
 impl Intrinsic for Sub {
-    fn name(self: &Self) -> &'static str {
+    fn name(&self) -> &'static str {
         "-"
     }
 
-    fn eval(self: &Self, evaluator: &Evaluator, env: &mut Env, args: Value) -> Value {
-        let mut total = car(&args).clone().as_num();
-        for value in cdr(&args).as_vec() {
-            total -= match evaluator.evaluate(env, &value) {
-                Num(n) => n,
-                _ => 0.0,
-            }
+    fn arity(&self) -> Arity {
+        Arity::at_least(1)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let mut xs = numbers(self.name(), args)?;
+        let head = xs.next().unwrap_or_default();
+        if args.len() == 1 {
+            return Ok(Num(-head));
         }
-        Num(total)
+        Ok(Num(xs.fold(head, |total, x| total - x)))
     }
 }
 
+/// Division following IEEE 754, so dividing by zero gives an infinity, or
+/// NaN for `(/ 0 0)`. The integer divisions `quot`, `mod` and `rem` fail
+/// instead.
 struct Div;
 impl Intrinsic for Div {
-    fn name(self: &Self) -> &'static str {
+    fn name(&self) -> &'static str {
         "/"
     }
 
-    fn eval(self: &Self, evaluator: &Evaluator, env: &mut Env, args: Value) -> Value {
-        let mut total = car(&args).clone().as_num();
-        for value in cdr(&args).as_vec() {
-            total /= match evaluator.evaluate(env, &value) {
-                Num(n) => n,
-                _ => 0.0,
-            }
+    fn arity(&self) -> Arity {
+        Arity::at_least(1)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let mut xs = numbers(self.name(), args)?;
+        let head = xs.next().unwrap_or_default();
+        if args.len() == 1 {
+            return Ok(Num(1.0 / head));
         }
-        Num(total)
+        Ok(Num(xs.fold(head, |total, x| total / x)))
     }
 }
 
-struct Eqauals;
-impl Intrinsic for Eqauals {
-    fn name(self: &Self) -> &'static str {
+/// The arguments of arithmetic intrinsic `name`, all of which must be
+/// numbers.
+fn numbers<'a>(name: &str, args: &'a [Value]) -> Result<impl Iterator<Item = f64> + 'a, EvalError> {
+    if let Some((i, v)) = args.iter().enumerate().find(|(_, v)| v.as_num().is_none()) {
+        return Err(type_error(name, i, "number", v));
+    }
+    Ok(args.iter().filter_map(Value::as_num))
+}
+
+struct Equals;
+impl Intrinsic for Equals {
+    fn name(&self) -> &'static str {
         "="
     }
 
-    fn eval(self: &Self, evaluator: &Evaluator, env: &mut Env, args: Value) -> Value {
-        let head = car(&args);
-        match cdr(&args) {
-            List(xs) => {
-                for arg in xs {
-                    let value = evaluator.eval(env, arg);
-                    if head.clone() != value {
-                        return Value::Bool(false);
-                    }
-                }
-                return Value::Bool(true);
-            }
-            _ => Value::Bool(false),
+    fn arity(&self) -> Arity {
+        Arity::at_least(1)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let head = &args[0];
+        Ok(Bool(args[1..].iter().all(|v| v == head)))
+    }
+}
+
+/// Chained comparison of numbers or strings, true when `test` holds for
+/// every adjacent pair.
+struct Compare {
+    name: &'static str,
+    test: fn(Ordering) -> bool,
+}
+
+impl Intrinsic for Compare {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::at_least(1)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let expected = match &args[0] {
+            Num(_) | Str(_) => args[0].type_name(),
+            v => return Err(type_error(self.name, 0, "number or string", v)),
+        };
+        if let Some((i, v)) = args
+            .iter()
+            .enumerate()
+            .find(|(_, v)| v.type_name() != expected)
+        {
+            return Err(type_error(self.name, i, expected, v));
         }
+        Ok(Bool(args.windows(2).all(|pair| {
+            let ordering = match (&pair[0], &pair[1]) {
+                (Num(a), Num(b)) => a.partial_cmp(b),
+                (Str(a), Str(b)) => Some(a.cmp(b)),
+                _ => None,
+            };
+            ordering.is_some_and(self.test)
+        })))
+    }
+}
+
+/// A type error for the argument at `position`, counting from zero.
+fn type_error(name: &str, position: usize, expected: &str, got: &Value) -> EvalError {
+    EvalError::Type {
+        name: name.into(),
+        position: position + 1,
+        expected: expected.into(),
+        got: got.type_name().into(),
+    }
+}
+
+struct Call;
+impl Intrinsic for Call {
+    fn name(&self) -> &'static str {
+        "call"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::at_least(2)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let handle = convert_arg::<Handle>(self.name(), 0, &args[0])?;
+        match &args[1] {
+            Atom(method) | Str(method) => handle.call_method(method, &args[2..]),
+            v => Err(type_error(self.name(), 1, "atom or string", v)),
+        }
+    }
+}
+
+/// Stands in for an intrinsic the evaluator is not allowed to use.
+struct Denied {
+    name: &'static str,
+    capability: Capability,
+}
+
+impl Intrinsic for Denied {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, _args: &[Value]) -> EvalResult {
+        Err(EvalError::PermissionDenied {
+            name: self.name.into(),
+            capability: self.capability,
+        })
+    }
+}
+
+fn core_intrinsics(evaluator: &mut Evaluator) {
+    evaluator.add_intrinsic(Eval {});
+    evaluator.add_intrinsic(Equals {});
+    evaluator.add_intrinsic(Add {});
+    evaluator.add_intrinsic(Mul {});
+    evaluator.add_intrinsic(Sub {});
+    evaluator.add_intrinsic(Div {});
+    evaluator.add_intrinsic(Compare {
+        name: "<",
+        test: Ordering::is_lt,
+    });
+    evaluator.add_intrinsic(Compare {
+        name: ">",
+        test: Ordering::is_gt,
+    });
+    evaluator.add_intrinsic(Compare {
+        name: "<=",
+        test: Ordering::is_le,
+    });
+    evaluator.add_intrinsic(Compare {
+        name: ">=",
+        test: Ordering::is_ge,
+    });
+    evaluator.define_constant("none", Value::None);
+    evaluator.register_fn("not", |v: Value| !v.is_true());
+    evaluator.add_intrinsic(Call {});
+    evaluator.add_intrinsic(modules::InNs {});
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Evaluator {
+    /// An evaluator with every capability.
     pub fn new() -> Self {
-        let mut this = Self {
-            intrinsics: HashMap::new(),
-        };
+        Self::with_capabilities(Capabilities::all())
+    }
+
+    /// An evaluator for untrusted code, without access to files, the
+    /// environment or processes.
+    pub fn sandboxed() -> Self {
+        Self::with_capabilities(Capabilities::sandbox())
+    }
+
+    pub fn with_capabilities(capabilities: Capabilities) -> Self {
+        let mut this = Self::empty(capabilities);
         this.base_intrinsics();
         this
     }
 
-    pub fn is_intrinsic<T: ToString>(self: &Self, s: T) -> bool {
-        self.intrinsics.contains_key(&s.to_string())
+    fn empty(capabilities: Capabilities) -> Self {
+        Self {
+            intrinsics: SymbolMap::default(),
+            capabilities,
+            constants: SymbolMap::default(),
+            rng: Cell::new(math::Rng::from_time()),
+            output: RefCell::new(Box::new(stdio::stdout())),
+            error_output: RefCell::new(Box::new(stdio::stderr())),
+            input: RefCell::new(Box::new(BufReader::new(stdio::stdin()))),
+            fuel: Cell::new(None),
+            max_depth: Cell::new(Some(DEFAULT_MAX_DEPTH)),
+            depth: Cell::new(0),
+            interrupt: InterruptHandle::default(),
+            memory_limit: Cell::new(Some(DEFAULT_MEMORY_LIMIT)),
+            account: Rc::default(),
+            groups: SymbolMap::default(),
+            namespaces: RefCell::default(),
+            module_files: RefCell::default(),
+            aliases: RefCell::default(),
+            namespace: Cell::new(None),
+            loading: RefCell::default(),
+            module_paths: Vec::new(),
+            trace: RefCell::default(),
+            trace_limit: Cell::new(32),
+            lenient_lookup: Cell::new(false),
+            checked_arithmetic: Cell::new(false),
+        }
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Frees environments kept alive only by reference cycles, such as a
+    /// function stored in the frame it closes over. Returns the number of
+    /// frames freed. Frames are tracked per thread, so this collects those
+    /// of every evaluator on the current thread.
+    pub fn collect_garbage(&self) -> usize {
+        gc::collect()
+    }
+
+    /// Counters for all evaluators on the current thread, not just this
+    /// one. Its `live_bytes` includes values the host created outside of
+    /// evaluation; `memory_used` counts only this evaluator's.
+    pub fn memory_stats(&self) -> MemoryStats {
+        gc::stats()
     }
 
-    pub fn add_intrinsic<T: Intrinsic + 'static>(self: &mut Self, intr: T) {
+    /// Reseeds the generator behind `random` and friends, as the
+    /// `random-seed` intrinsic does, so runs can be repeated.
+    pub fn seed_random(&self, seed: u64) {
+        self.rng.set(math::Rng::new(seed));
+    }
+
+    fn with_rng<T>(&self, f: impl FnOnce(&mut math::Rng) -> T) -> T {
+        let mut rng = self.rng.get();
+        let result = f(&mut rng);
+        self.rng.set(rng);
+        result
+    }
+
+    /// Limits the number of evaluation steps, after which evaluation fails
+    /// with `EvalError::OutOfFuel` until more fuel is given. `None` removes
+    /// the limit.
+    pub fn set_fuel(&self, fuel: Option<u64>) {
+        self.fuel.set(fuel);
+    }
+
+    /// The fuel left, `None` if unlimited.
+    pub fn fuel(&self) -> Option<u64> {
+        self.fuel.get()
+    }
+
+    /// Limits how deeply forms being evaluated may nest, failing with
+    /// `EvalError::DepthExceeded` beyond it. Every list form counts a
+    /// level, so a call of a function takes several: the call, its body
+    /// and the forms in it. The limit is `DEFAULT_MAX_DEPTH` unless
+    /// changed, which fits the 8 MB stack of a main thread; hosts
+    /// evaluating on threads with smaller stacks should lower it. `None`
+    /// removes the limit, and deep recursion then overflows the stack.
+    pub fn set_max_depth(&self, depth: Option<usize>) {
+        self.max_depth.set(depth);
+    }
+
+    /// Makes unbound symbols evaluate to `none` instead of failing with
+    /// `EvalError::Unbound`, for scripts written against older releases.
+    /// Calling an unbound name is an error either way.
+    pub fn set_lenient_lookup(&self, lenient: bool) {
+        self.lenient_lookup.set(lenient);
+    }
+
+    /// Makes intrinsics that return NaN or an infinity, such as `(/ 1 0)`
+    /// or `(sqrt -1)`, fail with `EvalError::Arithmetic` instead.
+    pub fn set_checked_arithmetic(&self, checked: bool) {
+        self.checked_arithmetic.set(checked);
+    }
+
+    /// A handle that can stop evaluation from another thread.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.interrupt.clone()
+    }
+
+    /// Limits the bytes that the frames, bindings, list cells and strings
+    /// created by this evaluator's scripts may hold at once, failing with
+    /// `EvalError::OutOfMemory` beyond it. Memory is given back as values
+    /// are freed. The limit is `DEFAULT_MEMORY_LIMIT` unless changed, and
+    /// `None` removes it, leaving huge allocations such as `(range 1e12)`
+    /// to abort the process.
+    pub fn set_memory_limit(&self, limit: Option<usize>) {
+        self.memory_limit.set(limit);
+    }
+
+    /// Bytes held by live values this evaluator's scripts created.
+    pub fn memory_used(&self) -> usize {
+        self.account.live()
+    }
+
+    /// Checks that `bytes` more can be allocated within the memory limit.
+    /// Intrinsics building large values call this before allocating.
+    pub fn reserve(&self, bytes: usize) -> Result<(), EvalError> {
+        match self.memory_limit.get() {
+            Some(limit) if self.memory_used().saturating_add(bytes) > limit => {
+                Err(EvalError::OutOfMemory(limit))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Like `reserve`, for a list of `len` cells.
+    pub fn reserve_cells(&self, len: usize) -> Result<(), EvalError> {
+        self.reserve(len.saturating_mul(gc::CELL_BYTES))
+    }
+
+    /// Consumes one step of fuel and checks for interrupts and the memory
+    /// limit.
+    fn step(&self) -> Result<(), EvalError> {
+        if self.interrupt.take() {
+            return Err(EvalError::Interrupted);
+        }
+        self.reserve(0)?;
+        if let Some(fuel) = self.fuel.get() {
+            if fuel == 0 {
+                return Err(EvalError::OutOfFuel);
+            }
+            self.fuel.set(Some(fuel - 1));
+        }
+        Ok(())
+    }
+
+    /// Sends the output of `print` and `println` to `output`.
+    pub fn set_output<W: Write + 'static>(&mut self, output: W) {
+        self.output = RefCell::new(Box::new(output));
+    }
+
+    /// Sends the output of `eprint` and `eprintln` to `output`.
+    pub fn set_error_output<W: Write + 'static>(&mut self, output: W) {
+        self.error_output = RefCell::new(Box::new(output));
+    }
+
+    /// Makes `read-line` read from `input`.
+    pub fn set_input<R: BufRead + 'static>(&mut self, input: R) {
+        self.input = RefCell::new(Box::new(input));
+    }
+
+    /// Collects everything printed from now on into the returned buffer.
+    pub fn capture_output(&mut self) -> OutputBuffer {
+        let buffer = OutputBuffer::new();
+        self.set_output(buffer.clone());
+        buffer
+    }
+
+    /// Collects everything printed to the error output into the returned
+    /// buffer.
+    pub fn capture_error_output(&mut self) -> OutputBuffer {
+        let buffer = OutputBuffer::new();
+        self.set_error_output(buffer.clone());
+        buffer
+    }
+
+    /// Binds `name` for every script run by this evaluator. Environment
+    /// bindings of the same name take precedence.
+    pub fn define_constant<T: Into<Symbol>>(&mut self, name: T, value: Value) {
+        self.constants.insert(name.into(), value);
+    }
+
+    pub fn is_intrinsic<T: Into<Symbol>>(&self, s: T) -> bool {
+        self.intrinsics.contains_key(&s.into())
+    }
+
+    pub fn add_intrinsic<T: Intrinsic + 'static>(&mut self, intr: T) {
         self.intrinsics
-            .insert(intr.name().to_string(), Box::new(intr));
+            .insert(Symbol::new(intr.name()), Box::new(intr));
+    }
+
+    /// Registers a Rust closure as an intrinsic. Arguments are converted
+    /// with `FromValue`, so mismatched types become `EvalError::Type`.
+    pub fn register_fn<Args, F>(&mut self, name: &'static str, func: F)
+    where
+        F: NativeFn<Args> + 'static,
+        Args: 'static,
+    {
+        self.add_intrinsic(native::NativeIntrinsic {
+            name,
+            func,
+            args: PhantomData,
+        });
     }
 
-    pub fn base_intrinsics(self: &mut Self) {
-        self.add_intrinsic(Eval {});
-        self.add_intrinsic(Eqauals {});
-        self.add_intrinsic(Add {});
-        self.add_intrinsic(Mul {});
-        self.add_intrinsic(Sub {});
-        self.add_intrinsic(Div {});
+    /// Installs the intrinsic groups allowed by the evaluator's
+    /// capabilities.
+    pub fn base_intrinsics(&mut self) {
+        self.install(Capability::Core, core_intrinsics);
+        self.install(Capability::Core, lists::register);
+        self.install(Capability::Core, exceptions::register);
+        self.install(Capability::Math, math::register);
+        self.install(Capability::Strings, strings::register);
+        self.install(Capability::Console, io::register_console);
+        self.install(Capability::Files, io::register_files);
+        self.install(Capability::Os, os::register_os);
+        self.install(Capability::Process, os::register_process);
+    }
+
+    /// Registers a group of intrinsics, or placeholders that deny access
+    /// to them if `capability` is not allowed.
+    fn install(&mut self, capability: Capability, register: fn(&mut Evaluator)) {
+        let mut group = Evaluator::empty(Capabilities::none());
+        register(&mut group);
+        let allowed = self.capabilities.allows(capability);
+        if allowed {
+            self.constants.extend(group.constants);
+        }
+        for (name, intr) in group.intrinsics {
+            self.groups.insert(name, capability);
+            if allowed {
+                self.intrinsics.insert(name, intr);
+            } else {
+                self.add_intrinsic(Denied {
+                    name: name.as_str(),
+                    capability,
+                });
+            }
+        }
     }
 
     pub fn evaluate_if(
-        self: &Self,
-        env: &mut Env,
+        &self,
+        env: &Env,
         cond: &Value,
         if_true: &Value,
         if_false: &Value,
-    ) -> Value {
-        if self.evaluate(env, cond).is_true() {
-            self.evaluate(env, if_true).clone()
+    ) -> EvalResult {
+        if self.evaluate(env, cond)?.is_true() {
+            self.evaluate(env, if_true)
+        } else {
+            self.evaluate(env, if_false)
+        }
+    }
+
+    fn expect_args(&self, name: &str, expected: Arity, got: usize) -> Result<(), EvalError> {
+        if expected.accepts(got) {
+            Ok(())
         } else {
-            self.evaluate(env, if_false).clone()
+            Err(EvalError::Arity {
+                name: name.to_string(),
+                expected,
+                got,
+            })
         }
     }
 
+    /// Borrows the first `N` arguments of a special form after checking
+    /// their count; missing optional arguments are `Value::None`.
+    fn form_args<'a, const N: usize>(
+        &self,
+        name: Symbol,
+        expected: Arity,
+        args: &'a List,
+    ) -> Result<[&'a Value; N], EvalError> {
+        self.expect_args(name.as_str(), expected, args.len())?;
+        let mut it = args.iter();
+        Ok([(); N].map(|_| it.next().unwrap_or(&Value::None)))
+    }
+
     pub fn evaluate_special_form(
-        self: &Self,
-        env: &mut Env,
-        ident: &String,
-        args: &Vec<Value>,
-    ) -> Option<Value> {
-        match ident.as_str() {
-            "do" => {
-                let mut result = Value::None;
+        &self,
+        env: &Env,
+        ident: Symbol,
+        args: &List,
+    ) -> Option<EvalResult> {
+        let result = match ident {
+            Symbol::DO => {
+                let mut result = Ok(Value::None);
                 for arg in args {
                     result = self.evaluate(env, arg);
+                    if result.is_err() {
+                        break;
+                    }
                 }
-                Some(result)
+                result
             }
-            "if" => {
-                if args.len() < 2 {
-                    panic!("If is missing arguments");
+            Symbol::IF => self.form_args(ident, Arity::between(2, 3), args).and_then(
+                |[cond, if_true, if_false]| self.evaluate_if(env, cond, if_true, if_false),
+            ),
+            Symbol::DEF => self
+                .form_args::<2>(ident, Arity::exactly(2), args)
+                .and_then(|args| {
+                    let sym = match args[0] {
+                        Sym(s) => s,
+                        v => return Err(EvalError::Syntax(format!("Cannot def {}", v))),
+                    };
+                    let value = self.evaluate(env, args[1])?;
+                    env.set(sym, value.clone());
+                    Ok(value)
+                }),
+            Symbol::SET => self
+                .form_args::<2>(ident, Arity::exactly(2), args)
+                .and_then(|args| {
+                    let sym = match args[0] {
+                        Sym(s) => s,
+                        v => return Err(EvalError::Syntax(format!("Cannot set {}", v))),
+                    };
+                    let value = self.evaluate(env, args[1])?;
+                    if !env.assign(sym, value.clone()) {
+                        return Err(self.unbound(env, *sym));
+                    }
+                    Ok(value)
+                }),
+            Symbol::FUN => self
+                .form_args::<2>(ident, Arity::at_least(2), args)
+                .and_then(|forms| {
+                    let fname = match forms[0] {
+                        Sym(s) if !env.has(s) => *s,
+                        v => {
+                            return Err(EvalError::Syntax(format!("Cannot define function {}", v)))
+                        }
+                    };
+                    let body = args.rest().rest();
+                    let func = self.make_function(env, Some(fname), forms[1], body)?;
+                    env.set(fname, func.clone());
+                    Ok(func)
+                }),
+            Symbol::FN => self
+                .form_args(ident, Arity::at_least(1), args)
+                .and_then(|[params]| self.make_function(env, None, params, args.rest())),
+            Symbol::QUOTE => self
+                .form_args(ident, Arity::exactly(1), args)
+                .map(|[quoted]| quoted.clone()),
+            // Both stop at the first value that decides the result and
+            // return it.
+            Symbol::AND => {
+                let mut result = Ok(Bool(true));
+                for arg in args {
+                    result = self.evaluate(env, arg);
+                    if !result.as_ref().is_ok_and(Value::is_true) {
+                        break;
+                    }
                 }
-                Some(
-                    self.evaluate_if(
-                        env,
-                        &args[0],
-                        &args[1],
-                        if args.len() > 2 {
-                            &args[2]
-                        } else {
-                            &Value::None
-                        },
-                    )
-                    .clone(),
-                )
+                result
             }
-            "def" => {
-                let sym = &args[0];
-                assert!(matches!(sym, Sym(_)));
-                let value = self.evaluate(env, &args[1]);
-                env.set(sym.to_string(), value.clone());
-                Some(value)
+            Symbol::OR => {
+                let mut result = Ok(Bool(false));
+                for arg in args {
+                    result = self.evaluate(env, arg);
+                    if !result.as_ref().is_ok_and(|v| !v.is_true()) {
+                        break;
+                    }
+                }
+                result
             }
-            "set" => {
-                let sym = &args[0];
-                assert!(matches!(sym, Sym(_)));
-                assert!(env.has(sym.to_string()));
-                let value = self.evaluate(env, &args[1]);
-                env.set(sym.to_string(), value.clone());
-                Some(value)
+            Symbol::MODULE => self.evaluate_module(args),
+            Symbol::IMPORT => self.evaluate_import(env, args),
+            Symbol::TRY => self.evaluate_try(env, args),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// Functions share the environment they are defined in. The body shares
+    /// structure with the defining form.
+    fn make_function(
+        &self,
+        env: &Env,
+        name: Option<Symbol>,
+        params: &Value,
+        body: List,
+    ) -> EvalResult {
+        match params {
+            Value::List(ps) if ps.is_proper() && ps.iter().all(|p| matches!(p, Sym(_))) => {}
+            v => return Err(EvalError::Syntax(format!("Invalid parameter list {}", v))),
+        }
+        Ok(Func(OwlFunc::new(
+            name.map(|s| s.to_string()),
+            params.clone(),
+            Value::List(body.prepend(Sym(Symbol::DO))),
+            env.clone(),
+        )))
+    }
+
+    pub fn call_intrinsic(&self, env: &Env, intr: &dyn Intrinsic, args: &List) -> EvalResult {
+        let args = if intr.raw() {
+            args.to_vec()
+        } else {
+            self.evaluate_args(env, args)?
+        };
+        self.invoke_intrinsic(env, intr, &args)
+    }
+
+    fn evaluate_args(&self, env: &Env, args: &List) -> Result<Vec<Value>, EvalError> {
+        args.iter().map(|arg| self.evaluate(env, arg)).collect()
+    }
+
+    fn invoke_intrinsic(&self, env: &Env, intr: &dyn Intrinsic, args: &[Value]) -> EvalResult {
+        self.expect_args(intr.name(), intr.arity(), args.len())?;
+        let result = intr.eval(self, env, args)?;
+        match &result {
+            Str(s) => self.reserve(s.len())?,
+            Num(n) if self.checked_arithmetic.get() && !n.is_finite() => {
+                return Err(EvalError::Arithmetic {
+                    name: intr.name().into(),
+                    message: if n.is_nan() {
+                        "result is not a number".into()
+                    } else {
+                        "result is infinite".into()
+                    },
+                });
             }
-            "fun" => {
-                let sym = &args[0];
-                assert!(matches!(sym, Sym(_)));
-                assert!(!env.has(sym.to_string()));
-                Some(Value::None)
+            _ => {}
+        }
+        self.reserve(0)?;
+        Ok(result)
+    }
+
+    /// Applies a function value to already evaluated arguments.
+    pub fn apply(&self, func: &Value, args: &[Value]) -> EvalResult {
+        match func {
+            Func(f) => gc::with_account(&self.account, || self.apply_func(f, args)),
+            _ => self.apply_in(&Env::new(), func, args),
+        }
+    }
+
+    /// Like `apply`, but intrinsics such as `eval` run in `env`.
+    pub fn apply_in(&self, env: &Env, func: &Value, args: &[Value]) -> EvalResult {
+        gc::with_account(&self.account, || match func {
+            Func(f) => self.apply_func(f, args),
+            Value::Intrinsic(name) => match self.intrinsics.get(name) {
+                Some(intr) => self.invoke_intrinsic(env, intr.as_ref(), args),
+                None => Err(self.unbound(env, *name)),
+            },
+            v => Err(EvalError::NotCallable(v.type_name().into())),
+        })
+    }
+
+    fn apply_func(&self, func: &OwlFunc, args: &[Value]) -> EvalResult {
+        let params = match func.params() {
+            Value::List(params) => params,
+            _ => return Err(EvalError::Syntax("Invalid parameter list".into())),
+        };
+        self.expect_args(
+            func.name().unwrap_or("fn"),
+            Arity::exactly(params.len()),
+            args.len(),
+        )?;
+        let scope = func.env().child();
+        for (param, arg) in params.iter().zip(args) {
+            if let Sym(param) = param {
+                scope.set(param, arg.clone());
             }
-            _ => None,
         }
+        self.evaluate(&scope, func.body())
+    }
+
+    /// Calls the function or intrinsic bound to `name` with Rust arguments,
+    /// converting the result back into `R`.
+    pub fn call<R: FromValue, A: IntoArgs>(
+        &self,
+        env: &Env,
+        name: &str,
+        args: A,
+    ) -> Result<R, EvalError> {
+        let args = args.into_args();
+        self.clear_backtrace();
+        let sym = Symbol::new(name);
+        let result = gc::with_account(&self.account, || {
+            Ok(match env.find(sym) {
+                Some(func) => self.apply(&func, &args)?,
+                None => match self.intrinsics.get(&sym) {
+                    Some(intr) => self.invoke_intrinsic(env, intr.as_ref(), &args)?,
+                    None => match self.resolve_qualified(sym) {
+                        Some(func) => self.apply_in(env, &func, &args)?,
+                        None => return Err(self.unbound(env, sym)),
+                    },
+                },
+            })
+        })?;
+        // Hosts may call into scripts without ever evaluating code again.
+        gc::maybe_collect();
+        let got = result.type_name();
+        R::from_value(result).ok_or_else(|| EvalError::ReturnType {
+            name: name.into(),
+            expected: R::expected(),
+            got: got.into(),
+        })
     }
 
-    pub fn evaluate(self: &Self, env: &mut Env, value: &Value) -> Value {
+    pub fn evaluate(&self, env: &Env, value: &Value) -> EvalResult {
+        self.step()?;
         match value {
-            Num(_) | Str(_) | Atom(_) | Bool(_) | Func(_) | Value::None => value.clone(),
-            Sym(s) => env.get(s.into()).clone(),
-            List(xs) if xs.len() == 0 => value.clone(),
-            List(xs) => {
-                let ident = xs.get(0).unwrap_or(&Value::None).to_string();
-                let args = &xs[1..].to_vec();
-
-                match self.evaluate_special_form(env, &ident, args) {
-                    Some(v) => return v,
-                    None => {} // not a special form
-                }
+            Num(_)
+            | Str(_)
+            | Atom(_)
+            | Bool(_)
+            | Func(_)
+            | Native(_)
+            | Value::Intrinsic(_)
+            | Value::Error(_)
+            | Value::None => Ok(value.clone()),
+            // Unbound names of intrinsics evaluate to the intrinsic itself,
+            // so they can be passed to functions such as `map`.
+            Sym(s) => match env.find(*s) {
+                Some(value) => Ok(value),
+                None => match self.constants.get(s) {
+                    Some(value) => Ok(value.clone()),
+                    None if self.is_intrinsic(*s) => Ok(Value::Intrinsic(*s)),
+                    None => match self.resolve_qualified(*s) {
+                        Some(value) => Ok(value),
+                        None if self.lenient_lookup.get() => Ok(Value::None),
+                        None => Err(self.unbound(env, *s)),
+                    },
+                },
+            },
+            Value::List(xs) if xs.is_empty() => Ok(value.clone()),
+            Value::List(xs) if !xs.is_proper() => Err(EvalError::Syntax(format!(
+                "Cannot evaluate dotted list {}",
+                value
+            ))),
+            Value::List(xs) => self
+                .nested(|| self.evaluate_list(env, xs))
+                .map_err(|e| self.trace_form(e, xs)),
+        }
+    }
 
-                if self.is_intrinsic(&ident) {
-                    let intr = self
-                        .intrinsics
-                        .get(&ident.clone())
-                        .expect("Failed to get intrinsic")
-                        .as_ref();
-                    return intr.eval(&self, env, Value::List(args.clone()));
-                }
+    /// Runs `f` one level deeper, failing if that exceeds the limit.
+    fn nested(&self, f: impl FnOnce() -> EvalResult) -> EvalResult {
+        let depth = self.depth.get();
+        if let Some(limit) = self.max_depth.get().filter(|&limit| depth >= limit) {
+            return Err(EvalError::DepthExceeded(limit));
+        }
+        self.depth.set(depth + 1);
+        let result = f();
+        self.depth.set(depth);
+        result
+    }
 
-                Value::None
+    /// Evaluates a special form or call.
+    fn evaluate_list(&self, env: &Env, xs: &List) -> EvalResult {
+        let head = xs.first().unwrap_or(&Value::None);
+        let args = &xs.rest();
+        if let Sym(ident) = *head {
+            if let Some(result) = self.evaluate_special_form(env, ident, args) {
+                return result;
+            }
+            if env.find(ident).is_none() {
+                let intr = match self.intrinsics.get(&ident) {
+                    Some(intr) => Some(intr),
+                    None => match self.resolve_qualified(ident) {
+                        Some(Value::Intrinsic(name)) => self.intrinsics.get(&name),
+                        Some(_) => None,
+                        None => return Err(self.unbound(env, ident)),
+                    },
+                };
+                if let Some(intr) = intr {
+                    let args = if intr.raw() {
+                        args.to_vec()
+                    } else {
+                        self.evaluate_args(env, args)?
+                    };
+                    return self
+                        .invoke_intrinsic(env, intr.as_ref(), &args)
+                        .map_err(|e| self.trace_call(e, xs, intr.name(), &args));
+                }
             }
         }
+
+        let func = self.evaluate(env, head)?;
+        let args = self.evaluate_args(env, args)?;
+        self.apply_in(env, &func, &args).map_err(|e| {
+            let name = match &func {
+                Func(f) => f.name().unwrap_or("fn"),
+                Value::Intrinsic(name) => name.as_str(),
+                v => v.type_name(),
+            };
+            self.trace_call(e, xs, name, &args)
+        })
     }
 
-    pub fn eval<T: ToString>(self: &Self, env: &mut Env, code: T) -> Value {
-        let mut reader = Reader::new();
+    pub fn eval<T: ToString>(&self, env: &Env, code: T) -> EvalResult {
+        self.eval_source(env, Source::new("<eval>", code.to_string()))
+    }
 
-        match reader.read_script(&code.to_string()) {
-            Ok(v) => self.evaluate(env, &v),
-            Err(e) => {
-                println!("Error {:?}", e);
-                Value::None
+    /// Evaluates a script whose forms remember where in `source` they
+    /// were read, for backtraces.
+    fn eval_source(&self, env: &Env, source: Rc<Source>) -> EvalResult {
+        self.clear_backtrace();
+        let result = gc::with_account(&self.account, || {
+            let script = self.read_source(&source)?;
+            // Forms run one by one, as `in-ns` changes where the next one
+            // runs.
+            let mut result = Ok(Value::None);
+            if let Value::List(forms) = &script {
+                for form in &forms.rest() {
+                    result = self.evaluate(&self.top_level(env), form);
+                    if result.is_err() {
+                        break;
+                    }
+                }
             }
-        }
+            result
+        });
+        gc::maybe_collect();
+        result
+    }
+
+    /// Reads `source` as a script, recording where the reader stopped if it
+    /// is malformed.
+    pub(super) fn read_source(&self, source: &Rc<Source>) -> EvalResult {
+        let mut reader = Reader::with_source(source.clone());
+        reader.read_script(&source.text).map_err(|e| {
+            self.trace_reader(source, reader.it, reader.unclosed());
+            EvalError::Reader(e)
+        })
     }
 }
 
-pub fn eval<T: ToString>(code: T) -> Value {
-    let mut env = Env::new();
-    Evaluator::new().eval(&mut env, code)
+pub fn eval<T: ToString>(code: T) -> EvalResult {
+    Evaluator::new().eval(&Env::new(), code)
 }
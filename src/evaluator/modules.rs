@@ -0,0 +1,315 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::values::{Env, List, Source, Symbol, Value};
+
+use super::{Arity, Capability, EvalError, EvalResult, Evaluator, Intrinsic};
+
+/// A table of names reached through qualified symbols such as
+/// `parser/parse`. Modules expose only the names they export, namespaces
+/// entered with `in-ns` everything they define.
+pub(super) struct Namespace {
+    env: Env,
+    exports: Option<Vec<Symbol>>,
+}
+
+impl Namespace {
+    fn get(&self, name: Symbol) -> Option<Value> {
+        match &self.exports {
+            Some(exports) if !exports.contains(&name) => None,
+            _ => self.env.find_local(name),
+        }
+    }
+
+    fn exported(&self) -> Vec<(Symbol, Value)> {
+        let names = self.exports.clone().unwrap_or_else(|| self.env.names());
+        names
+            .into_iter()
+            .filter_map(|name| Some((name, self.env.find_local(name)?)))
+            .collect()
+    }
+}
+
+/// A file being evaluated, with the modules it has declared so far.
+pub(super) struct Loading {
+    path: PathBuf,
+    declared: Vec<Symbol>,
+}
+
+impl Evaluator {
+    /// Adds a directory searched by `import` after the importing file's
+    /// own directory.
+    pub fn add_module_path<P: Into<PathBuf>>(&mut self, dir: P) {
+        self.module_paths.push(dir.into());
+    }
+
+    /// Evaluates the script at `path` in `env`. Imports in it are resolved
+    /// relative to the script.
+    pub fn eval_file<P: AsRef<Path>>(&self, env: &Env, path: P) -> EvalResult {
+        let path = path.as_ref();
+        let source = read_file(path)?;
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.with_loading(path, || self.eval_source(env, source)).0
+    }
+
+    /// Reads the script at `path` without evaluating it, failing with the
+    /// first reader error in it.
+    pub fn check_file<P: AsRef<Path>>(&self, path: P) -> Result<(), EvalError> {
+        self.clear_backtrace();
+        let source = read_file(path.as_ref())?;
+        self.read_source(&source).map(drop)
+    }
+
+    /// Runs `f` with `path` as the file being evaluated, outside of any
+    /// namespace entered by the importer.
+    fn with_loading(&self, path: PathBuf, f: impl FnOnce() -> EvalResult) -> (EvalResult, Loading) {
+        self.loading.borrow_mut().push(Loading {
+            path,
+            declared: Vec::new(),
+        });
+        let namespace = self.namespace.replace(None);
+        let result = f();
+        self.namespace.set(namespace);
+        let loading = self.loading.borrow_mut().pop().expect("loading stack");
+        (result, loading)
+    }
+
+    /// The namespace `in-ns` last switched to, `user` for the global
+    /// environment.
+    pub fn namespace(&self) -> Symbol {
+        self.namespace.get().unwrap_or_else(|| Symbol::new(USER))
+    }
+
+    /// The environment top-level forms are evaluated in: that of the
+    /// current namespace, or `env` outside of one.
+    pub(super) fn top_level(&self, env: &Env) -> Env {
+        self.namespace
+            .get()
+            .and_then(|name| self.namespaces.borrow().get(&name).map(|ns| ns.env.clone()))
+            .unwrap_or_else(|| env.clone())
+    }
+
+    /// The value of a qualified symbol, looked up in the namespace or
+    /// alias it names. Built-in intrinsics are qualified by the name of
+    /// their capability, as in `math/sqrt`, or its short name, as in
+    /// `str.split`.
+    pub(super) fn resolve_qualified(&self, sym: Symbol) -> Option<Value> {
+        let (ns, name) = sym.qualified()?;
+        let ns = self.aliases.borrow().get(&ns).copied().unwrap_or(ns);
+        if let Some(namespace) = self.namespaces.borrow().get(&ns) {
+            return namespace.get(name);
+        }
+        match self.groups.get(&name) {
+            Some(capability)
+                if capability.to_string() == ns.as_str()
+                    || capability.short_name() == Some(ns.as_str()) =>
+            {
+                Some(Value::Intrinsic(name))
+            }
+            _ => None,
+        }
+    }
+
+    /// `(module name (export names...) body...)` evaluates the body in a
+    /// fresh environment and makes it the namespace `name`, exposing only
+    /// the exported names.
+    pub(super) fn evaluate_module(&self, args: &List) -> EvalResult {
+        let [name, exports] = self.form_args(Symbol::MODULE, Arity::at_least(2), args)?;
+        let name = match name {
+            Value::Sym(s) => *s,
+            v => return Err(EvalError::Syntax(format!("Invalid module name {}", v))),
+        };
+        let names = match exports {
+            Value::List(xs) if xs.first() == Some(&Value::Sym(Symbol::new("export"))) => xs
+                .rest()
+                .iter()
+                .map(|x| match x {
+                    Value::Sym(s) => Ok(*s),
+                    v => Err(EvalError::Syntax(format!("Cannot export {}", v))),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            v => {
+                return Err(EvalError::Syntax(format!(
+                    "Expected an export list in module {} but got {}",
+                    name, v
+                )))
+            }
+        };
+
+        let scope = Env::new();
+        for form in &args.rest().rest() {
+            self.evaluate(&scope, form)?;
+        }
+        if let Some(missing) = names.iter().find(|export| !scope.has(**export)) {
+            return Err(EvalError::Syntax(format!(
+                "Module {} exports {}, which it does not define",
+                name, missing
+            )));
+        }
+
+        self.namespaces.borrow_mut().insert(
+            name,
+            Rc::new(Namespace {
+                env: scope,
+                exports: Some(names),
+            }),
+        );
+        if let Some(loading) = self.loading.borrow_mut().last_mut() {
+            loading.declared.push(name);
+        }
+        Ok(Value::None)
+    }
+
+    /// `(import "path.owl")` or `(import name)` binds a module's exports in
+    /// `env`. With `:as alias` they are left in the module's namespace and
+    /// reached as `alias/export` instead.
+    pub(super) fn evaluate_import(&self, env: &Env, args: &List) -> EvalResult {
+        let [target, keyword, alias] =
+            self.form_args(Symbol::IMPORT, Arity::between(1, 3), args)?;
+        let prefix = match (keyword, alias) {
+            (Value::None, Value::None) => None,
+            (Value::Atom(a), Value::Sym(alias)) if a == "as" => Some(*alias),
+            _ => {
+                return Err(EvalError::Syntax(format!(
+                    "Invalid import {}",
+                    Value::List(args.clone())
+                )))
+            }
+        };
+        let name = match target {
+            Value::Str(path) => self.import_file(Path::new(path))?,
+            Value::Sym(name) if self.namespaces.borrow().contains_key(name) => *name,
+            Value::Sym(name) => self.import_file(Path::new(&format!("{}.owl", name)))?,
+            v => return Err(EvalError::Syntax(format!("Cannot import {}", v))),
+        };
+        match prefix {
+            Some(alias) => {
+                self.aliases.borrow_mut().insert(alias, name);
+            }
+            None => {
+                let namespace = self.namespaces.borrow()[&name].clone();
+                for (name, value) in namespace.exported() {
+                    env.set(name, value);
+                }
+            }
+        }
+        Ok(Value::None)
+    }
+
+    /// Loads the module declared by the file at `path`, evaluating the file
+    /// only the first time it is imported, and returns its name.
+    fn import_file(&self, path: &Path) -> Result<Symbol, EvalError> {
+        if !self.capabilities.allows(Capability::Files) {
+            return Err(EvalError::PermissionDenied {
+                name: Symbol::IMPORT.to_string(),
+                capability: Capability::Files,
+            });
+        }
+        let path = self
+            .resolve_module(path)
+            .ok_or_else(|| EvalError::ModuleNotFound(path.display().to_string()))?;
+
+        if let Some(&name) = self.module_files.borrow().get(&path) {
+            return Ok(name);
+        }
+        let cycle = {
+            let loading = self.loading.borrow();
+            loading.iter().position(|l| l.path == path).map(|start| {
+                loading[start..]
+                    .iter()
+                    .map(|l| &l.path)
+                    .chain([&path])
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+            })
+        };
+        if let Some(cycle) = cycle {
+            return Err(EvalError::ImportCycle(cycle));
+        }
+
+        let code = fs::read_to_string(&path)
+            .map_err(|e| EvalError::Io(format!("import: {}: {}", path.display(), e)))?;
+        let source = Source::new(path.display().to_string(), code);
+        let (result, loading) =
+            self.with_loading(path.clone(), || self.eval_source(&Env::new(), source));
+        result?;
+        let name = match loading.declared[..] {
+            [name] => name,
+            _ => {
+                return Err(EvalError::Syntax(format!(
+                    "{} must declare exactly one module",
+                    path.display()
+                )))
+            }
+        };
+        self.module_files.borrow_mut().insert(path, name);
+        Ok(name)
+    }
+
+    /// Looks for `path` next to the importing file, or in the working
+    /// directory outside of files, and then in the module paths.
+    fn resolve_module(&self, path: &Path) -> Option<PathBuf> {
+        let base = match self.loading.borrow().last() {
+            Some(loading) => loading.path.parent().map(Path::to_path_buf),
+            None => env::current_dir().ok(),
+        };
+        base.iter()
+            .chain(&self.module_paths)
+            .map(|dir| dir.join(path))
+            .find(|candidate| candidate.is_file())
+            .and_then(|found| found.canonicalize().ok())
+    }
+}
+
+fn read_file(path: &Path) -> Result<Rc<Source>, EvalError> {
+    let code = fs::read_to_string(path)
+        .map_err(|e| EvalError::Io(format!("{}: {}", path.display(), e)))?;
+    Ok(Source::new(path.display().to_string(), code))
+}
+
+/// The namespace of the global environment.
+const USER: &str = "user";
+
+/// `(in-ns name)` evaluates later top-level forms in the namespace `name`,
+/// creating it if needed, so its definitions do not clobber global ones.
+/// `(in-ns user)` returns to the global environment.
+pub(super) struct InNs;
+impl Intrinsic for InNs {
+    fn name(&self) -> &'static str {
+        "in-ns"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn raw(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, evaluator: &Evaluator, env: &Env, args: &[Value]) -> EvalResult {
+        let name = match &args[0] {
+            Value::Sym(name) => *name,
+            v => return Err(EvalError::Syntax(format!("Invalid namespace {}", v))),
+        };
+        if name == USER {
+            evaluator.namespace.set(None);
+            return Ok(Value::None);
+        }
+        evaluator
+            .namespaces
+            .borrow_mut()
+            .entry(name)
+            .or_insert_with(|| {
+                Rc::new(Namespace {
+                    env: env.root().child(),
+                    exports: None,
+                })
+            });
+        evaluator.namespace.set(Some(name));
+        Ok(Value::None)
+    }
+}
@@ -0,0 +1,106 @@
+use std::marker::PhantomData;
+
+use crate::values::{Env, FromValue, IntoValue, Value};
+
+use super::{Arity, EvalError, EvalResult, Evaluator, Intrinsic};
+
+/// A Rust closure that can be called from Owl. Implemented for closures of
+/// up to six arguments whose parameters implement `FromValue` and whose
+/// result implements `IntoValue`.
+pub trait NativeFn<Args> {
+    fn arity(&self) -> usize;
+    fn call(&self, name: &str, args: &[Value]) -> EvalResult;
+}
+
+/// Converts the argument at `position` (zero based), reporting a type error
+/// that names the function and the one-based argument position.
+pub fn convert_arg<T: FromValue>(
+    name: &str,
+    position: usize,
+    value: &Value,
+) -> Result<T, EvalError> {
+    T::from_value(value.clone()).ok_or_else(|| EvalError::Type {
+        name: name.to_string(),
+        position: position + 1,
+        expected: T::expected(),
+        got: value.type_name().to_string(),
+    })
+}
+
+macro_rules! native_fn {
+    ($n:expr; $($arg:ident: $idx:tt),*) => {
+        impl<F, R, $($arg),*> NativeFn<($($arg,)*)> for F
+        where
+            F: Fn($($arg),*) -> R,
+            R: IntoValue,
+            $($arg: FromValue),*
+        {
+            fn arity(&self) -> usize {
+                $n
+            }
+
+            #[allow(unused_variables)]
+            fn call(&self, name: &str, args: &[Value]) -> EvalResult {
+                Ok(self($(convert_arg::<$arg>(name, $idx, &args[$idx])?),*).into_value())
+            }
+        }
+    };
+}
+
+native_fn!(0;);
+native_fn!(1; A: 0);
+native_fn!(2; A: 0, B: 1);
+native_fn!(3; A: 0, B: 1, C: 2);
+native_fn!(4; A: 0, B: 1, C: 2, D: 3);
+native_fn!(5; A: 0, B: 1, C: 2, D: 3, E: 4);
+native_fn!(6; A: 0, B: 1, C: 2, D: 3, E: 4, G: 5);
+
+/// Arguments passed from Rust into an Owl function, either a `Vec<Value>`
+/// or a tuple of values implementing `IntoValue`.
+pub trait IntoArgs {
+    fn into_args(self) -> Vec<Value>;
+}
+
+impl IntoArgs for Vec<Value> {
+    fn into_args(self) -> Vec<Value> {
+        self
+    }
+}
+
+macro_rules! into_args {
+    ($($arg:ident: $idx:tt),*) => {
+        impl<$($arg: IntoValue),*> IntoArgs for ($($arg,)*) {
+            fn into_args(self) -> Vec<Value> {
+                vec![$(self.$idx.into_value()),*]
+            }
+        }
+    };
+}
+
+into_args!();
+into_args!(A: 0);
+into_args!(A: 0, B: 1);
+into_args!(A: 0, B: 1, C: 2);
+into_args!(A: 0, B: 1, C: 2, D: 3);
+into_args!(A: 0, B: 1, C: 2, D: 3, E: 4);
+into_args!(A: 0, B: 1, C: 2, D: 3, E: 4, G: 5);
+
+pub(crate) struct NativeIntrinsic<F, Args> {
+    pub name: &'static str,
+    pub func: F,
+    pub args: PhantomData<fn(Args)>,
+}
+
+impl<F: NativeFn<Args>, Args> Intrinsic for NativeIntrinsic<F, Args> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(self.func.arity())
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        self.func.call(self.name, args)
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    env,
+    io::Write,
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::values::{Env, Value};
+
+use super::{convert_arg, Arity, EvalError, EvalResult, Evaluator, Intrinsic};
+
+pub(super) fn register_os(evaluator: &mut Evaluator) {
+    evaluator.register_fn("getenv", |name: String| env::var(name).ok());
+    evaluator.register_fn("args", || env::args().skip(1).collect::<Vec<_>>());
+    evaluator.register_fn("now", || {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |d| d.as_secs_f64())
+    });
+}
+
+pub(super) fn register_process(evaluator: &mut Evaluator) {
+    evaluator.add_intrinsic(Run {});
+    evaluator.add_intrinsic(Exit {});
+}
+
+/// `(exit code)` flushes the evaluator's output and ends the process.
+struct Exit;
+impl Intrinsic for Exit {
+    fn name(&self) -> &'static str {
+        "exit"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let code = convert_arg::<i64>(self.name(), 0, &args[0])?;
+        for stream in [&evaluator.output, &evaluator.error_output] {
+            if let Ok(mut stream) = stream.try_borrow_mut() {
+                let _ = stream.flush();
+            }
+        }
+        process::exit(code as i32)
+    }
+}
+
+/// `(run program args...)` waits for the command and returns the list
+/// `(status stdout stderr)`.
+struct Run;
+impl Intrinsic for Run {
+    fn name(&self) -> &'static str {
+        "run"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::at_least(1)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let args = args
+            .iter()
+            .enumerate()
+            .map(|(i, v)| convert_arg::<String>(self.name(), i, v))
+            .collect::<Result<Vec<_>, _>>()?;
+        let output = process::Command::new(&args[0])
+            .args(&args[1..])
+            .output()
+            .map_err(|e| EvalError::Io(format!("{}: {}: {}", self.name(), args[0], e)))?;
+        Ok(Value::list(vec![
+            output
+                .status
+                .code()
+                .map_or(Value::None, |c| Value::Num(c as f64)),
+            Value::Str(String::from_utf8_lossy(&output.stdout).into_owned()),
+            Value::Str(String::from_utf8_lossy(&output.stderr).into_owned()),
+        ]))
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt::Write;
+
+use crate::values::{Env, Value};
+
+use super::{convert_arg, Arity, EvalError, EvalResult, Evaluator, Intrinsic};
+
+// Lengths and indices count characters, not bytes.
+
+pub(super) fn register(evaluator: &mut Evaluator) {
+    evaluator.register_fn("string-length", |s: String| s.chars().count() as i64);
+    evaluator.add_intrinsic(Substring {});
+    evaluator.add_intrinsic(StringAppend {});
+    evaluator.register_fn("split", |s: String, sep: String| -> Vec<String> {
+        if sep.is_empty() {
+            s.chars().map(String::from).collect()
+        } else {
+            s.split(sep.as_str()).map(String::from).collect()
+        }
+    });
+    evaluator.add_intrinsic(Join {});
+    evaluator.register_fn("trim", |s: String| s.trim().to_string());
+    evaluator.register_fn("upcase", |s: String| s.to_uppercase());
+    evaluator.register_fn("downcase", |s: String| s.to_lowercase());
+    evaluator.register_fn("starts-with?", |s: String, prefix: String| {
+        s.starts_with(&prefix)
+    });
+    evaluator.register_fn("ends-with?", |s: String, suffix: String| {
+        s.ends_with(&suffix)
+    });
+    evaluator.register_fn("contains?", |s: String, sub: String| s.contains(&sub));
+    evaluator.add_intrinsic(Replace {});
+    evaluator.register_fn("index-of", |s: String, sub: String| {
+        s.find(&sub).map(|i| s[..i].chars().count() as i64)
+    });
+    evaluator.register_fn("string->number", |s: String| {
+        s.trim().parse::<f64>().ok().filter(|n| n.is_finite())
+    });
+    evaluator.register_fn("number->string", |n: f64| Value::Num(n).to_string());
+    evaluator.register_fn("string->list", |s: String| -> Vec<String> {
+        s.chars().map(String::from).collect()
+    });
+    evaluator.add_intrinsic(Format {});
+}
+
+/// `(substring s start)` or `(substring s start end)`, with `end` exclusive.
+struct Substring;
+impl Intrinsic for Substring {
+    fn name(&self) -> &'static str {
+        "substring"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::between(2, 3)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let s = convert_arg::<String>(self.name(), 0, &args[0])?;
+        let len = s.chars().count() as i64;
+        let start = convert_arg::<i64>(self.name(), 1, &args[1])?;
+        let end = match args.get(2) {
+            Some(end) => convert_arg::<i64>(self.name(), 2, end)?,
+            None => len,
+        };
+        if start < 0 || start > end || end > len {
+            return Err(EvalError::Native(format!(
+                "substring: range {} to {} is out of bounds for length {}",
+                start, end, len
+            )));
+        }
+        Ok(Value::Str(
+            s.chars()
+                .skip(start as usize)
+                .take((end - start) as usize)
+                .collect(),
+        ))
+    }
+}
+
+struct StringAppend;
+impl Intrinsic for StringAppend {
+    fn name(&self) -> &'static str {
+        "string-append"
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let parts = args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| convert_arg::<String>(self.name(), i, arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        evaluator.reserve(parts.iter().map(String::len).sum())?;
+        Ok(Value::Str(parts.concat()))
+    }
+}
+
+/// `(join xs sep)` concatenates a list of strings with `sep` between them.
+struct Join;
+impl Intrinsic for Join {
+    fn name(&self) -> &'static str {
+        "join"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(2)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let xs = convert_arg::<Vec<String>>(self.name(), 0, &args[0])?;
+        let sep = convert_arg::<String>(self.name(), 1, &args[1])?;
+        let len =
+            xs.iter().map(String::len).sum::<usize>() + sep.len() * xs.len().saturating_sub(1);
+        evaluator.reserve(len)?;
+        Ok(Value::Str(xs.join(&sep)))
+    }
+}
+
+/// `(replace s from to)` replaces every occurrence of `from`.
+struct Replace;
+impl Intrinsic for Replace {
+    fn name(&self) -> &'static str {
+        "replace"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(3)
+    }
+
+    fn eval(&self, evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let s = convert_arg::<String>(self.name(), 0, &args[0])?;
+        let from = convert_arg::<String>(self.name(), 1, &args[1])?;
+        let to = convert_arg::<String>(self.name(), 2, &args[2])?;
+        if from.is_empty() {
+            return Ok(Value::Str(s));
+        }
+        let growth = to.len().saturating_sub(from.len());
+        evaluator.reserve(s.len() + s.matches(&from).count() * growth)?;
+        Ok(Value::Str(s.replace(&from, &to)))
+    }
+}
+
+/// `(format "{} has {1} {0}" a b)`: `{}` takes the next argument and `{n}`
+/// the one at position `n`, counting from zero. `{{` and `}}` are literal
+/// braces.
+struct Format;
+impl Intrinsic for Format {
+    fn name(&self) -> &'static str {
+        "format"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::at_least(1)
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        let template = convert_arg::<String>(self.name(), 0, &args[0])?;
+        let values = &args[1..];
+        let error = |msg: String| Err(EvalError::Native(format!("format: {}", msg)));
+
+        let mut result = String::new();
+        let mut next = 0;
+        let mut chars = template.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    result.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    result.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let Some(close) = rest.find('}') else {
+                        return error("unclosed placeholder".into());
+                    };
+                    let position = match &rest[..close] {
+                        "" => {
+                            next += 1;
+                            next - 1
+                        }
+                        n => match n.parse::<usize>() {
+                            Ok(n) => n,
+                            Err(_) => return error(format!("invalid placeholder {{{}}}", n)),
+                        },
+                    };
+                    let Some(value) = values.get(position) else {
+                        return error(format!("no argument for placeholder {}", position));
+                    };
+                    let _ = write!(result, "{}", value);
+                    chars = rest[close + 1..].chars();
+                }
+                '}' => return error("unmatched }".into()),
+                ch => result.push(ch),
+            }
+        }
+        Ok(Value::Str(result))
+    }
+}
@@ -0,0 +1,64 @@
+use crate::values::{Env, Symbol};
+
+use super::{EvalError, Evaluator};
+
+/// Most names suggested for an unbound one.
+const MAX_SUGGESTIONS: usize = 3;
+
+impl Evaluator {
+    /// The error for looking up `name` where it is not bound, suggesting
+    /// names bound in `env` or built in that are spelled alike.
+    pub(super) fn unbound(&self, env: &Env, name: Symbol) -> EvalError {
+        EvalError::Unbound {
+            name: name.to_string(),
+            suggestions: self.similar_names(env, name),
+        }
+    }
+
+    fn similar_names(&self, env: &Env, name: Symbol) -> Vec<String> {
+        let mut candidates = Vec::new();
+        let mut scope = Some(env.clone());
+        while let Some(env) = scope {
+            candidates.extend(env.names());
+            scope = env.parent();
+        }
+        candidates.extend(self.constants.keys());
+        candidates.extend(self.intrinsics.keys());
+
+        let name = name.as_str();
+        // As rustc does, allow about one typo per three characters.
+        let limit = (name.chars().count() / 3).max(1);
+        let mut close = candidates
+            .into_iter()
+            .map(|candidate| candidate.as_str())
+            .filter(|candidate| *candidate != name)
+            .filter_map(|candidate| {
+                let distance = edit_distance(name, candidate);
+                (distance <= limit).then_some((distance, candidate))
+            })
+            .collect::<Vec<_>>();
+        close.sort_unstable();
+        close.dedup();
+        close
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, candidate)| candidate.to_string())
+            .collect()
+    }
+}
+
+/// The Levenshtein distance between `a` and `b`, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
@@ -0,0 +1,255 @@
+use std::{fmt, rc::Rc};
+
+use crate::diagnostics::Diagnostic;
+use crate::reader::ReaderError;
+use crate::values::{List, Source, Span, Value};
+
+use super::{EvalError, Evaluator};
+
+/// Longest rendering of the arguments kept in a frame, in characters.
+const MAX_ARGS_WIDTH: usize = 60;
+
+/// A call that an error passed through on its way out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// The function or intrinsic called.
+    pub name: String,
+    /// The arguments as written in scripts, shortened if long.
+    pub args: String,
+    /// Where the call was made, if it was read from a source.
+    pub span: Option<Span>,
+}
+
+/// The calls an error unwound through, innermost first. Frames beyond the
+/// evaluator's backtrace limit are only counted.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Backtrace {
+    pub frames: Vec<Frame>,
+    pub omitted: usize,
+    /// The innermost form the error was raised in, or where the reader
+    /// stopped.
+    pub origin: Option<Span>,
+    /// The delimiter a reader error found unclosed.
+    pub unclosed: Option<Span>,
+}
+
+impl Backtrace {
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.span {
+            Some(span) => write!(f, "{}: ", span)?,
+            None => write!(f, "<native>: ")?,
+        }
+        if self.args.is_empty() {
+            write!(f, "({})", self.name)
+        } else {
+            write!(f, "({} {})", self.name, self.args)
+        }
+    }
+}
+
+/// Formats like a Python traceback, with the most recent call last.
+impl fmt::Display for Backtrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "traceback (most recent call last):")?;
+        if self.omitted > 0 {
+            writeln!(f, "  ... {} earlier calls", self.omitted)?;
+        }
+        for frame in self.frames.iter().rev() {
+            writeln!(f, "  {}", frame)?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects at most `MAX_ARGS_WIDTH` characters, failing once full so that
+/// rendering huge or deeply nested arguments stops early.
+#[derive(Default)]
+struct Shortened {
+    text: String,
+    chars: usize,
+    cut: bool,
+}
+
+impl fmt::Write for Shortened {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for ch in s.chars() {
+            if self.chars == MAX_ARGS_WIDTH {
+                self.cut = true;
+                return Err(fmt::Error);
+            }
+            self.text.push(ch);
+            self.chars += 1;
+        }
+        Ok(())
+    }
+}
+
+fn render_args(args: &[Value]) -> String {
+    let mut out = Shortened::default();
+    let _ = args.iter().enumerate().try_for_each(|(i, arg)| {
+        if i > 0 {
+            fmt::Write::write_str(&mut out, " ")?;
+        }
+        fmt::write(&mut out, format_args!("{:#}", arg))
+    });
+    if out.cut {
+        out.text.push_str("...");
+    }
+    out.text
+}
+
+impl Evaluator {
+    /// The calls the last error returned by `eval`, `eval_file` or `call`
+    /// passed through.
+    pub fn backtrace(&self) -> Backtrace {
+        self.trace.borrow().clone()
+    }
+
+    /// Limits how many frames a backtrace keeps, so deep recursion stays
+    /// readable. Only the innermost frames are kept.
+    pub fn set_backtrace_limit(&self, limit: usize) {
+        self.trace_limit.set(limit);
+    }
+
+    pub(super) fn clear_backtrace(&self) {
+        *self.trace.borrow_mut() = Backtrace::default();
+    }
+
+    /// Records the first form with a known source that `error` leaves.
+    pub(super) fn trace_form(&self, error: EvalError, form: &List) -> EvalError {
+        let mut trace = self.trace.borrow_mut();
+        if trace.origin.is_none() {
+            trace.origin = form.span().cloned();
+        }
+        error
+    }
+
+    /// Records where the reader stopped reading `source`.
+    pub(super) fn trace_reader(&self, source: &Rc<Source>, at: usize, unclosed: Option<usize>) {
+        // Point just past the last character rather than at trailing
+        // whitespace when the reader ran out of input.
+        let at = at.min(source.text.trim_end().len());
+        let point = |start: usize| {
+            let len = source.text[start..]
+                .chars()
+                .next()
+                .filter(|&c| c != '\n')
+                .map_or(0, char::len_utf8);
+            Span {
+                source: source.clone(),
+                start,
+                end: start + len,
+            }
+        };
+        let mut trace = self.trace.borrow_mut();
+        trace.origin = Some(point(at));
+        trace.unclosed = unclosed.map(point);
+    }
+
+    /// Describes `error`, the last error returned by `eval`, `eval_file` or
+    /// `call`, pointing at the source it was raised in.
+    pub fn diagnose(&self, error: &EvalError) -> Diagnostic {
+        let trace = self.trace.borrow();
+        let mut diagnostic = Diagnostic::error(error.to_string()).with_code(error.code());
+        if let Some(origin) = &trace.origin {
+            // Symbols have no span of their own, so point at the first use
+            // of an unbound name in the form it was looked up in.
+            let span = match error {
+                EvalError::Unbound { name, .. } => find_token(origin, name),
+                _ => None,
+            };
+            let span = span.unwrap_or_else(|| origin.clone());
+            diagnostic = diagnostic.with_primary(span, primary_note(error));
+        }
+        if let Some(unclosed) = &trace.unclosed {
+            diagnostic = diagnostic.with_related(unclosed.clone(), "opened here");
+        }
+        match help(error) {
+            Some(help) => diagnostic.with_help(help),
+            None => diagnostic,
+        }
+    }
+
+    /// Records that `error` left the call `form` of `name` with `args`.
+    pub(super) fn trace_call(
+        &self,
+        error: EvalError,
+        form: &List,
+        name: &str,
+        args: &[Value],
+    ) -> EvalError {
+        let mut trace = self.trace.borrow_mut();
+        if trace.frames.len() < self.trace_limit.get() {
+            trace.frames.push(Frame {
+                name: name.into(),
+                args: render_args(args),
+                span: form.span().cloned(),
+            });
+        } else {
+            trace.omitted += 1;
+        }
+        error
+    }
+}
+
+/// The first occurrence of `token` in `span` that is not part of a longer
+/// token.
+fn find_token(span: &Span, token: &str) -> Option<Span> {
+    let text = span.text();
+    let is_delimiter = |c: char| c.is_whitespace() || "()[]{}'\"".contains(c);
+    text.match_indices(token)
+        .map(|(i, _)| i)
+        .find(|&i| {
+            let end = i + token.len();
+            text[..i].chars().next_back().is_none_or(is_delimiter)
+                && text[end..].chars().next().is_none_or(is_delimiter)
+        })
+        .map(|i| Span {
+            source: span.source.clone(),
+            start: span.start + i,
+            end: span.start + i + token.len(),
+        })
+}
+
+fn primary_note(error: &EvalError) -> String {
+    match error {
+        EvalError::Reader(ReaderError::UnbalancedParenthesis) => "expected `)`".into(),
+        EvalError::Reader(ReaderError::UnbalancedBraces) => "expected `}`".into(),
+        EvalError::Arity { got, .. } => format!("called with {} arguments", got),
+        EvalError::Type { expected, got, .. } => format!("expected {}, found {}", expected, got),
+        EvalError::Unbound { .. } => "not defined".into(),
+        EvalError::NotCallable(_) => "not a function".into(),
+        EvalError::Thrown(_) => "thrown here".into(),
+        _ => String::new(),
+    }
+}
+
+fn help(error: &EvalError) -> Option<String> {
+    match error {
+        EvalError::Reader(ReaderError::UnbalancedParenthesis) => {
+            Some("every `(` needs a matching `)`".into())
+        }
+        EvalError::Reader(ReaderError::UnbalancedBraces) => {
+            Some("every `{` needs a matching `}`".into())
+        }
+        EvalError::Unbound { suggestions, .. } => match &suggestions[..] {
+            [] => None,
+            [name] => Some(format!("did you mean `{}`?", name)),
+            names => Some(format!("did you mean one of `{}`?", names.join("`, `"))),
+        },
+        EvalError::PermissionDenied { capability, .. } => Some(format!(
+            "the evaluator was created without the {} capability",
+            capability
+        )),
+        EvalError::Thrown(value) if !matches!(value, Value::Error(_)) => {
+            Some("throw a value made with `error` to give it a kind and message".into())
+        }
+        _ => None,
+    }
+}
@@ -1,3 +1,4 @@
+pub mod diagnostics;
 pub mod evaluator;
 pub mod reader;
 pub mod values;
@@ -1,20 +1,140 @@
-use evaluator::Intrinsic;
-
-use crate::{
-    evaluator::{eval, Evaluator},
-    values::{Env, Value},
-};
-
+pub mod diagnostics;
 pub mod evaluator;
 pub mod reader;
 pub mod values;
 
-use values::Value::Num;
+use std::{
+    env,
+    io::{self, BufRead, IsTerminal, Write},
+    process,
+};
+
+use evaluator::{EvalError, Evaluator};
+use values::{Env, Value};
+
+/// How errors are reported, chosen with `--error-format`.
+#[derive(Clone, Copy, PartialEq)]
+enum ErrorFormat {
+    /// Rendered with source snippets.
+    Human,
+    /// One JSON object per line, for editors and CI tools.
+    Json,
+}
+
+const USAGE: &str = "\
+usage: owl [run] [--error-format=human|json] [--lenient] [--checked] [script]
+       owl check [--error-format=human|json] scripts...";
+
+/// `owl run script` (or just `owl script`) runs a script, and starts a REPL
+/// without one. `owl check scripts...` only reads the scripts, reporting
+/// malformed ones. `--lenient` lets unbound symbols evaluate to `none`, as
+/// older releases did, and `--checked` makes NaN and infinite results
+/// errors. Modules are also searched for in the directories listed in
+/// `OWL_PATH`.
+fn main() {
+    let mut evaluator = Evaluator::new();
+    if let Some(dirs) = env::var_os("OWL_PATH") {
+        for dir in env::split_paths(&dirs) {
+            evaluator.add_module_path(dir);
+        }
+    }
+
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    let command = match args.first().map(String::as_str) {
+        Some("run" | "check") => args.remove(0),
+        _ => "run".to_string(),
+    };
+    let mut format = ErrorFormat::Human;
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.strip_prefix("--error-format=") {
+            Some("human") => format = ErrorFormat::Human,
+            Some("json") => format = ErrorFormat::Json,
+            Some(other) => usage(&format!("unknown error format `{}`", other)),
+            None if arg == "--lenient" => evaluator.set_lenient_lookup(true),
+            None if arg == "--checked" => evaluator.set_checked_arithmetic(true),
+            None if arg.starts_with("--") => usage(&format!("unknown option `{}`", arg)),
+            None => paths.push(arg),
+        }
+    }
+
+    let env = Env::new();
+    let result = match (command.as_str(), &paths[..]) {
+        ("check", []) => usage("no scripts to check"),
+        ("check", paths) => {
+            let mut result = Ok(());
+            for path in paths {
+                if let Err(e) = evaluator.check_file(path) {
+                    report(&evaluator, &e, format);
+                    result = Err(e);
+                }
+            }
+            result
+        }
+        ("run", []) => {
+            repl(&evaluator, &env, format);
+            Ok(())
+        }
+        ("run", [path]) => evaluator
+            .eval_file(&env, path)
+            .map(drop)
+            .inspect_err(|e| report(&evaluator, e, format)),
+        _ => usage("run takes a single script"),
+    };
+    if result.is_err() {
+        process::exit(1);
+    }
+}
+
+fn usage(problem: &str) -> ! {
+    eprintln!("error: {}\n{}", problem, USAGE);
+    process::exit(2);
+}
+
+/// Evaluates a line at a time and prints each result. The prompt names
+/// the current namespace.
+fn repl(evaluator: &Evaluator, env: &Env, format: ErrorFormat) {
+    let mut line = String::new();
+    loop {
+        print!("{}> ", evaluator.namespace());
+        let _ = io::stdout().flush();
+        line.clear();
+        match io::stdin().lock().read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        match evaluator.eval(env, &line) {
+            Ok(Value::None) => {}
+            Ok(value) => println!("{}", value),
+            Err(e) => report(evaluator, &e, format),
+        }
+    }
+}
+
+/// Prints an error after the calls it unwound through, or as a JSON
+/// object on its own line.
+fn report(evaluator: &Evaluator, error: &EvalError, format: ErrorFormat) {
+    let diagnostic = evaluator.diagnose(error);
+    match format {
+        ErrorFormat::Human => {
+            let backtrace = evaluator.backtrace();
+            if !backtrace.is_empty() {
+                eprint!("{}", backtrace);
+            }
+            eprint!("{}", diagnostic.render(io::stderr().is_terminal()));
+        }
+        ErrorFormat::Json => eprintln!("{}", diagnostic.to_json()),
+    }
+}
 
-fn main() {}
+#[cfg(test)]
+mod tests {
+    use crate::evaluator::eval;
+    use crate::values::Value::Num;
 
-#[test]
-fn evaluate_math_expressions() {
-    assert_eq!(eval("(+ 1 2 3)"), Num(6.0));
-    assert_eq!(eval("(+ 1 (+ 1 2) 3)"), Num(7.0));
+    #[test]
+    fn evaluate_math_expressions() {
+        assert_eq!(eval("(+ 1 2 3)"), Ok(Num(6.0)));
+        assert_eq!(eval("(+ 1 (+ 1 2) 3)"), Ok(Num(7.0)));
+    }
 }
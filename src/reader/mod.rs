@@ -1,12 +1,23 @@
-use std::{char, fmt::Pointer};
+use std::{fmt, rc::Rc};
 
-use crate::values::Value;
+use crate::values::{List, Source, Span, Symbol, Value};
+
+/// How deeply forms may nest before reading fails, so that deeply nested
+/// input cannot overflow the stack of the reader or the evaluator.
+pub const MAX_NESTING: usize = 500;
 
 pub struct Reader {
+    /// Byte offset of the next character.
     pub it: usize,
+    /// Lists read from a known source remember their span in it.
+    source: Option<Rc<Source>>,
+    /// Byte offset of the delimiter an unbalanced-delimiter error left open.
+    unclosed: Option<usize>,
+    /// How many forms are being read around the current one.
+    depth: usize,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum ReaderError {
     NotANumber,
     NotABoolean,
@@ -18,66 +29,141 @@ pub enum ReaderError {
     UnbalancedBraces,
     InvalidNumber(String),
     InvalidSymbol(String),
+    InvalidDottedPair,
     GenericError(String),
+    NestedTooDeeply,
+}
+
+impl fmt::Display for ReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReaderError::NotANumber => write!(f, "expected a number"),
+            ReaderError::NotABoolean => write!(f, "expected a boolean"),
+            ReaderError::NotAString => write!(f, "expected a string"),
+            ReaderError::NotAList => write!(f, "expected a list"),
+            ReaderError::NotAFunctionCall => write!(f, "expected a function call"),
+            ReaderError::UnterminatedString => write!(f, "unterminated string"),
+            ReaderError::UnbalancedParenthesis => write!(f, "unbalanced parenthesis"),
+            ReaderError::UnbalancedBraces => write!(f, "unbalanced braces"),
+            ReaderError::InvalidNumber(msg) => write!(f, "invalid number: {}", msg),
+            ReaderError::InvalidSymbol(msg) => write!(f, "invalid symbol: {}", msg),
+            ReaderError::InvalidDottedPair => write!(f, "invalid dotted pair"),
+            ReaderError::GenericError(msg) => write!(f, "{}", msg),
+            ReaderError::NestedTooDeeply => {
+                write!(f, "forms nested more than {} deep", MAX_NESTING)
+            }
+        }
+    }
+}
+
+impl ReaderError {
+    /// A code identifying the kind of error that stays the same across
+    /// releases, for tools that filter diagnostics.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ReaderError::NotANumber => "R0001",
+            ReaderError::NotABoolean => "R0002",
+            ReaderError::NotAString => "R0003",
+            ReaderError::NotAList => "R0004",
+            ReaderError::NotAFunctionCall => "R0005",
+            ReaderError::UnterminatedString => "R0006",
+            ReaderError::UnbalancedParenthesis => "R0007",
+            ReaderError::UnbalancedBraces => "R0008",
+            ReaderError::InvalidNumber(_) => "R0009",
+            ReaderError::InvalidSymbol(_) => "R0010",
+            ReaderError::InvalidDottedPair => "R0011",
+            ReaderError::GenericError(_) => "R0012",
+            ReaderError::NestedTooDeeply => "R0013",
+        }
+    }
 }
 
 type ReaderResult = Result<Value, ReaderError>;
 
+impl Default for Reader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Reader {
     pub fn new() -> Self {
-        Self { it: 0 }
+        Self {
+            it: 0,
+            source: None,
+            unclosed: None,
+            depth: 0,
+        }
+    }
+
+    /// A reader for the text of `source`, whose lists carry their spans.
+    pub fn with_source(source: Rc<Source>) -> Self {
+        Self {
+            it: 0,
+            source: Some(source),
+            unclosed: None,
+            depth: 0,
+        }
+    }
+
+    /// After an unbalanced parenthesis or brace error, the byte offset of
+    /// the delimiter that was never closed.
+    pub fn unclosed(&self) -> Option<usize> {
+        self.unclosed
     }
 
-    pub fn reset(self: &mut Self) {
+    pub fn reset(&mut self) {
         self.it = 0;
     }
 
-    pub fn at_eof(self: &Self, code: &String) -> bool {
+    pub fn at_eof(&self, code: &str) -> bool {
         self.it >= code.len()
     }
 
-    pub fn chr(self: &Self, code: &String) -> Option<char> {
-        code.chars().nth(self.it)
+    pub fn chr(&self, code: &str) -> Option<char> {
+        code.get(self.it..)?.chars().next()
+    }
+
+    /// Moves past the current character, which may be several bytes long.
+    pub fn advance(&mut self, code: &str) {
+        self.it += self.chr(code).map_or(1, char::len_utf8);
     }
 
-    pub fn is_chr_p(self: &Self, code: &String, f: fn(char) -> bool) -> bool {
-        self.chr(code).map_or(false, f)
+    pub fn is_chr_p(&self, code: &str, f: fn(char) -> bool) -> bool {
+        self.chr(code).is_some_and(f)
     }
-    pub fn is_chr(self: &Self, code: &String, chr: char) -> bool {
-        self.chr(code).map_or(false, |ch| ch == chr)
+    pub fn is_chr(&self, code: &str, chr: char) -> bool {
+        self.chr(code).is_some_and(|ch| ch == chr)
     }
 
-    pub fn is_whitespace(self: &Self, code: &String) -> bool {
-        code.chars()
-            .nth(self.it)
-            .map_or(false, |ch| ch.is_whitespace())
+    pub fn is_whitespace(&self, code: &str) -> bool {
+        self.chr(code).is_some_and(|ch| ch.is_whitespace())
     }
 
-    pub fn is_delimiter(self: &Self, code: &String) -> bool {
+    pub fn is_delimiter(&self, code: &str) -> bool {
         !self.at_eof(code)
-            && self.chr(code).map_or(false, |ch| match ch {
-                '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>' | '\'' => true,
-                _ => false,
-            })
+            && self
+                .chr(code)
+                .is_some_and(|ch| matches!(ch, '(' | ')' | '[' | ']' | '{' | '}' | '\''))
     }
 
-    pub fn skip_whitespace(self: &mut Self, code: &String) {
+    pub fn skip_whitespace(&mut self, code: &str) {
         while !self.at_eof(code) && self.is_whitespace(code) {
-            self.it += 1
+            self.advance(code);
         }
     }
 
-    pub fn read_boolean(self: &mut Self, code: &String) -> ReaderResult {
+    pub fn read_boolean(&mut self, code: &str) -> ReaderResult {
         let start = self.it;
         if !self.is_chr(code, '#') {
             return Err(ReaderError::NotABoolean);
         }
-        self.it += 1;
-        if self.chr(code).map_or(false, |ch| ch == 't' || ch == 'T') {
-            self.it += 1;
+        self.advance(code);
+        if self.chr(code).is_some_and(|ch| ch == 't' || ch == 'T') {
+            self.advance(code);
             Ok(Value::Bool(true))
-        } else if self.chr(code).map_or(false, |ch| ch == 'f' || ch == 'F') {
-            self.it += 1;
+        } else if self.chr(code).is_some_and(|ch| ch == 'f' || ch == 'F') {
+            self.advance(code);
             Ok(Value::Bool(false))
         } else {
             self.it = start;
@@ -85,20 +171,20 @@ impl Reader {
         }
     }
 
-    pub fn read_number(self: &mut Self, code: &String) -> ReaderResult {
+    pub fn read_number(&mut self, code: &str) -> ReaderResult {
         let start = self.it;
         let mut is_real = false;
 
         if self.is_chr(code, '-') {
-            self.it += 1;
+            self.advance(code);
         }
 
         if self.is_chr(code, '.') {
-            self.it += 1;
+            self.advance(code);
             is_real = true;
         }
 
-        if !self.chr(code).map_or(false, |ch| ch.is_digit(10)) {
+        if !self.chr(code).is_some_and(|ch| ch.is_ascii_digit()) {
             self.it = start;
             return Err(ReaderError::NotANumber);
         }
@@ -110,16 +196,17 @@ impl Reader {
                     return Err(ReaderError::InvalidNumber("Too many dots".into()));
                 } else {
                     is_real = true;
-                    self.it += 1;
+                    self.advance(code);
                     continue;
                 }
             }
 
-            if !self.chr(code).map_or(false, |ch| ch.is_digit(10)) {
+            if !self.chr(code).is_some_and(|ch| ch.is_ascii_digit()) {
                 if start == self.it {
                     return Err(ReaderError::NotANumber);
                 }
 
+                self.skip_exponent(code);
                 let sc = &code[start..self.it];
                 let n = sc
                     .parse::<f64>()
@@ -131,85 +218,146 @@ impl Reader {
                 break;
             }
 
-            self.it += 1;
+            self.advance(code);
         }
 
         Err(ReaderError::NotANumber)
     }
 
-    pub fn read_string(self: &mut Self, code: &String) -> ReaderResult {
+    /// Skips an exponent such as `e12` or `E-3` following a number.
+    fn skip_exponent(&mut self, code: &str) {
+        let rest = &code.as_bytes()[self.it..];
+        if !matches!(rest.first(), Some(b'e' | b'E')) {
+            return;
+        }
+        let sign = usize::from(matches!(rest.get(1), Some(b'+' | b'-')));
+        let digits = rest[1 + sign..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits > 0 {
+            self.it += 1 + sign + digits;
+        }
+    }
+
+    pub fn read_string(&mut self, code: &str) -> ReaderResult {
         let start = self.it;
         if !self.is_chr(code, '"') {
             return Err(ReaderError::NotAString);
         }
-        self.it += 1;
+        self.advance(code);
         while !self.at_eof(code) {
             if self.is_chr(code, '"') {
-                self.it += 1;
+                self.advance(code);
                 return Ok(Value::Str(code[start + 1..self.it - 1].into()));
             }
-            self.it += 1;
+            self.advance(code);
         }
         self.it = start;
         Err(ReaderError::UnterminatedString)
     }
 
-    pub fn read_symbol(self: &mut Self, code: &String) -> ReaderResult {
+    pub fn read_symbol(&mut self, code: &str) -> ReaderResult {
         let start = self.it;
         while !self.at_eof(code) && !self.is_whitespace(code) && !self.is_delimiter(code) {
-            self.it += 1;
+            self.advance(code);
         }
         if self.it == start {
             return Err(ReaderError::InvalidSymbol("Empty symbol".into()));
         }
-        return Ok(Value::Sym(code[start..self.it].into()));
+        match &code[start..self.it] {
+            atom if atom.len() > 1 && atom.starts_with(':') => Ok(Value::Atom(atom[1..].into())),
+            sym => Ok(Value::Sym(sym.into())),
+        }
+    }
+
+    /// A lone `.` separating the last element of a dotted pair.
+    fn is_dot(&self, code: &str) -> bool {
+        self.is_chr(code, '.')
+            && code[self.it + 1..]
+                .chars()
+                .next()
+                .is_none_or(|ch| ch.is_whitespace() || matches!(ch, '(' | ')'))
     }
 
-    pub fn read_list(self: &mut Self, code: &String) -> ReaderResult {
+    pub fn read_list(&mut self, code: &str) -> ReaderResult {
         let mut xs = Vec::new();
         if self.is_chr(code, '(') {
-            self.it += 1;
-            while !self.at_eof(code) {
-                let exp = self.read(code)?;
-                xs.push(exp);
+            let open = self.it;
+            self.advance(code);
+            loop {
                 self.skip_whitespace(code);
                 if self.at_eof(code) {
+                    self.unclosed = Some(open);
                     return Err(ReaderError::UnbalancedParenthesis);
                 } else if self.is_chr(code, ')') {
-                    self.it += 1;
-                    return Ok(Value::List(xs));
+                    self.advance(code);
+                    return Ok(Value::List(List::from(xs)));
+                } else if self.is_dot(code) {
+                    self.advance(code);
+                    return self.read_dotted_tail(code, open, xs);
                 }
+                xs.push(self.read(code)?);
             }
         }
         Err(ReaderError::NotAList)
     }
 
-    pub fn read_do_block(self: &mut Self, code: &String) -> ReaderResult {
+    fn read_dotted_tail(&mut self, code: &str, open: usize, xs: Vec<Value>) -> ReaderResult {
+        self.skip_whitespace(code);
+        if self.is_chr(code, ')') {
+            return Err(ReaderError::InvalidDottedPair);
+        }
+        let tail = self.read(code)?;
+        self.skip_whitespace(code);
+        if self.at_eof(code) {
+            self.unclosed = Some(open);
+            return Err(ReaderError::UnbalancedParenthesis);
+        } else if !self.is_chr(code, ')') {
+            return Err(ReaderError::InvalidDottedPair);
+        }
+        self.advance(code);
+        List::dotted(xs, tail)
+            .map(Value::List)
+            .ok_or(ReaderError::InvalidDottedPair)
+    }
+
+    /// `'x` reads as `(quote x)`.
+    pub fn read_quote(&mut self, code: &str) -> ReaderResult {
+        if !self.is_chr(code, '\'') {
+            return Err(ReaderError::NotAList);
+        }
+        self.advance(code);
+        let quoted = self.read(code)?;
+        Ok(Value::list(vec![Value::Sym(Symbol::QUOTE), quoted]))
+    }
+
+    pub fn read_do_block(&mut self, code: &str) -> ReaderResult {
         let mut xs = Vec::new();
         if self.is_chr(code, '{') {
-            self.it += 1;
-            while !self.at_eof(code) {
-                let exp = self.read(code)?;
-                xs.push(exp);
+            let open = self.it;
+            self.advance(code);
+            loop {
                 self.skip_whitespace(code);
                 if self.at_eof(code) {
+                    self.unclosed = Some(open);
                     return Err(ReaderError::UnbalancedBraces);
                 } else if self.is_chr(code, '}') {
-                    self.it += 1;
-                    xs.insert(0, Value::Sym("do".into()));
-                    return Ok(Value::List(xs));
+                    self.advance(code);
+                    return Ok(Value::List(List::from(xs).prepend(Value::Sym(Symbol::DO))));
                 }
+                xs.push(self.read(code)?);
             }
         }
         Err(ReaderError::NotAList)
     }
 
-    pub fn read_function_call(self: &mut Self, code: &String) -> ReaderResult {
+    pub fn read_function_call(&mut self, code: &str) -> ReaderResult {
         let start = self.it;
         let sym = self.read_symbol(code)?;
         let list = match self.read_list(code) {
             o @ Ok(_) => o,
-            e @ Err(ReaderError::UnbalancedParenthesis) => {
+            e @ Err(ReaderError::UnbalancedParenthesis | ReaderError::NestedTooDeeply) => {
                 return e;
             }
             e @ Err(_) => {
@@ -218,11 +366,7 @@ impl Reader {
             }
         }?;
         match list {
-            Value::List(xs) => {
-                let mut vs = xs;
-                vs.insert(0, sym);
-                Ok(Value::List(vs))
-            }
+            Value::List(xs) => Ok(Value::List(xs.prepend(sym))),
             _ => {
                 self.it = start;
                 Err(ReaderError::NotAFunctionCall)
@@ -230,40 +374,63 @@ impl Reader {
         }
     }
 
-    pub fn read(self: &mut Self, code: &String) -> ReaderResult {
+    pub fn read(&mut self, code: &str) -> ReaderResult {
         self.skip_whitespace(code);
+        let start = self.it;
+        if self.depth >= MAX_NESTING {
+            return Err(ReaderError::NestedTooDeeply);
+        }
+        self.depth += 1;
+        let form = self.read_form(code);
+        self.depth -= 1;
+        Ok(match (form?, &self.source) {
+            (Value::List(xs), Some(source)) => Value::List(xs.with_span(Span {
+                source: source.clone(),
+                start,
+                end: self.it,
+            })),
+            (form, _) => form,
+        })
+    }
 
+    fn read_form(&mut self, code: &str) -> ReaderResult {
         match self.read_number(code) {
             n @ Ok(_) => return n,
             e @ Err(ReaderError::InvalidNumber(_)) => return e,
             _ => {}
         }
 
-        match self.read_boolean(code) {
-            b @ Ok(_) => return b,
-            _ => {}
+        if let b @ Ok(_) = self.read_boolean(code) {
+            return b;
         }
 
-        match self.read_string(code) {
-            s @ Ok(_) => return s,
-            _ => {}
+        if let s @ Ok(_) = self.read_string(code) {
+            return s;
+        }
+
+        if self.is_chr(code, '\'') {
+            return self.read_quote(code);
         }
 
         match self.read_list(code) {
             s @ Ok(_) => return s,
-            e @ Err(ReaderError::UnbalancedParenthesis) => return e,
+            e @ Err(
+                ReaderError::UnbalancedParenthesis
+                | ReaderError::InvalidDottedPair
+                | ReaderError::NestedTooDeeply,
+            ) => return e,
             _ => {}
         }
 
         match self.read_do_block(code) {
             s @ Ok(_) => return s,
-            e @ Err(ReaderError::UnbalancedBraces) => return e,
+            e @ Err(ReaderError::UnbalancedBraces | ReaderError::NestedTooDeeply) => return e,
             _ => {}
         }
 
         match self.read_function_call(code) {
             s @ Ok(_) => return s,
-            e @ Err(ReaderError::UnbalancedParenthesis) => return e,
+            e @ Err(ReaderError::UnbalancedParenthesis | ReaderError::NestedTooDeeply) => return e,
             _ => {}
         }
 
@@ -273,11 +440,13 @@ impl Reader {
         }
     }
 
-    pub fn read_script(self: &mut Self, code: &String) -> ReaderResult {
-        let mut xs = vec![Value::Sym("do".into())];
+    pub fn read_script(&mut self, code: &str) -> ReaderResult {
+        let mut xs = vec![Value::Sym(Symbol::DO)];
+        self.skip_whitespace(code);
         while !self.at_eof(code) {
             xs.push(self.read(code)?);
+            self.skip_whitespace(code);
         }
-        Ok(Value::List(xs))
+        Ok(Value::list(xs))
     }
 }
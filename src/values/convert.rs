@@ -0,0 +1,260 @@
+use std::{collections::HashMap, rc::Rc};
+
+use super::{Handle, List, OwlError, Value};
+
+/// Conversion from an Owl value into a Rust type, used for the arguments
+/// of functions registered with `Evaluator::register_fn`.
+pub trait FromValue: Sized {
+    fn from_value(value: Value) -> Option<Self>;
+
+    /// Describes the accepted values in type errors.
+    fn expected() -> String;
+}
+
+/// Conversion from a Rust type into an Owl value.
+pub trait IntoValue {
+    fn into_value(self) -> Value;
+}
+
+impl FromValue for Value {
+    fn from_value(value: Value) -> Option<Self> {
+        Some(value)
+    }
+
+    fn expected() -> String {
+        "any value".into()
+    }
+}
+
+impl IntoValue for Value {
+    fn into_value(self) -> Value {
+        self
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Num(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        "number".into()
+    }
+}
+
+impl IntoValue for f64 {
+    fn into_value(self) -> Value {
+        Value::Num(self)
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Num(n) if n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64 => {
+                Some(n as i64)
+            }
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        "integer".into()
+    }
+}
+
+impl IntoValue for i64 {
+    fn into_value(self) -> Value {
+        Value::Num(self as f64)
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        "boolean".into()
+    }
+}
+
+impl IntoValue for bool {
+    fn into_value(self) -> Value {
+        Value::Bool(self)
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        "string".into()
+    }
+}
+
+impl IntoValue for String {
+    fn into_value(self) -> Value {
+        Value::Str(self)
+    }
+}
+
+impl IntoValue for &str {
+    fn into_value(self) -> Value {
+        Value::Str(self.to_string())
+    }
+}
+
+impl IntoValue for () {
+    fn into_value(self) -> Value {
+        Value::None
+    }
+}
+
+impl FromValue for List {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::List(xs) => Some(xs),
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        "list".into()
+    }
+}
+
+impl IntoValue for List {
+    fn into_value(self) -> Value {
+        Value::List(self)
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::List(xs) if xs.is_proper() => xs.iter().cloned().map(T::from_value).collect(),
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        format!("list of {}", T::expected())
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(self) -> Value {
+        Value::List(
+            self.into_iter()
+                .map(IntoValue::into_value)
+                .collect::<List>(),
+        )
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::None => Some(None),
+            v => T::from_value(v).map(Some),
+        }
+    }
+
+    fn expected() -> String {
+        format!("{} or none", T::expected())
+    }
+}
+
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn into_value(self) -> Value {
+        self.map_or(Value::None, IntoValue::into_value)
+    }
+}
+
+/// Maps are represented as association lists of `(key value)` pairs,
+/// keyed by strings, symbols or atoms.
+impl<T: FromValue> FromValue for HashMap<String, T> {
+    fn from_value(value: Value) -> Option<Self> {
+        Vec::<Value>::from_value(value)?
+            .into_iter()
+            .map(|entry| match entry {
+                Value::List(pair) if pair.len() == 2 && pair.is_proper() => {
+                    let value = T::from_value(pair.get(1)?.clone())?;
+                    match pair.first()?.clone() {
+                        Value::Str(k) | Value::Atom(k) => Some((k, value)),
+                        Value::Sym(k) => Some((k.to_string(), value)),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn expected() -> String {
+        format!("association list of {}", T::expected())
+    }
+}
+
+impl<T: IntoValue> IntoValue for HashMap<String, T> {
+    fn into_value(self) -> Value {
+        let mut entries = self.into_iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Value::List(
+            entries
+                .into_iter()
+                .map(|(k, v)| Value::list(vec![Value::Str(k), v.into_value()]))
+                .collect(),
+        )
+    }
+}
+
+impl FromValue for Handle {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Native(handle) => Some(handle),
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        "native handle".into()
+    }
+}
+
+impl IntoValue for Handle {
+    fn into_value(self) -> Value {
+        Value::Native(self)
+    }
+}
+
+impl FromValue for Rc<OwlError> {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Error(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        "error".into()
+    }
+}
+
+impl IntoValue for OwlError {
+    fn into_value(self) -> Value {
+        Value::Error(Rc::new(self))
+    }
+}
@@ -0,0 +1,318 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    mem::size_of,
+    rc::{Rc, Weak},
+};
+
+use super::{list::Cons, Frame, Symbol, Value};
+
+/// Bytes charged for one cons cell, including its reference counts.
+/// Strings held by the cell are charged on top.
+pub const CELL_BYTES: usize = size_of::<Cons>() + 2 * size_of::<usize>();
+/// Bytes charged for an environment frame.
+pub const FRAME_BYTES: usize = size_of::<RefCell<Frame>>() + 2 * size_of::<usize>();
+/// Bytes charged for each binding in a frame, plus the string it holds.
+pub const BINDING_BYTES: usize = size_of::<(Symbol, Value)>();
+
+/// The bytes held by the frames, bindings and list cells that an
+/// evaluator's scripts created and that are still alive.
+#[derive(Debug, Default)]
+pub struct Account {
+    live: Cell<usize>,
+}
+
+impl Account {
+    pub fn live(&self) -> usize {
+        self.live.get()
+    }
+}
+
+/// The bytes an object holds, charged to the account of the evaluator
+/// that was running when it was created. Objects created by the host
+/// outside of evaluation belong to no account until a script changes
+/// them.
+#[derive(Default)]
+pub(super) struct Charge {
+    account: Option<Rc<Account>>,
+}
+
+impl Charge {
+    pub(super) fn new(bytes: usize) -> Self {
+        HEAP.with(|heap| {
+            heap.live.set(heap.live.get().saturating_add(bytes));
+            let account = heap.account.borrow().clone();
+            if let Some(account) = &account {
+                account.live.set(account.live.get().saturating_add(bytes));
+            }
+            Self { account }
+        })
+    }
+
+    /// Charges an object that already holds `held` bytes to the running
+    /// evaluator, if it belongs to no account yet.
+    pub(super) fn adopt(&mut self, held: usize) {
+        if self.account.is_none() {
+            self.account = HEAP.with(|heap| heap.account.borrow().clone());
+            if let Some(account) = &self.account {
+                account.live.set(account.live.get().saturating_add(held));
+            }
+        }
+    }
+
+    pub(super) fn add(&self, bytes: usize) {
+        HEAP.with(|heap| heap.live.set(heap.live.get().saturating_add(bytes)));
+        if let Some(account) = &self.account {
+            account.live.set(account.live.get().saturating_add(bytes));
+        }
+    }
+
+    pub(super) fn release(&self, bytes: usize) {
+        // Objects may outlive the heap when the thread exits.
+        let _ = HEAP.try_with(|heap| heap.live.set(heap.live.get().saturating_sub(bytes)));
+        if let Some(account) = &self.account {
+            account.live.set(account.live.get().saturating_sub(bytes));
+        }
+    }
+}
+
+/// The bytes of a string held in a binding or list cell.
+pub(super) fn value_bytes(value: &Value) -> usize {
+    match value {
+        Value::Str(s) => s.len(),
+        _ => 0,
+    }
+}
+
+/// Runs `f` with objects created by it charged to `account`.
+pub fn with_account<T>(account: &Rc<Account>, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Rc<Account>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            let _ = HEAP.try_with(|heap| heap.account.replace(previous));
+        }
+    }
+
+    let previous = HEAP.with(|heap| heap.account.replace(Some(account.clone())));
+    let _restore = Restore(previous);
+    f()
+}
+
+/// Counters describing the environment frames on the current thread.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct MemoryStats {
+    /// Frames that are still alive.
+    pub frames: usize,
+    /// Number of collections run so far.
+    pub collections: usize,
+    /// Frames freed by the most recent collection.
+    pub last_collected: usize,
+    /// Frames freed by all collections.
+    pub total_collected: usize,
+    /// Bytes held by live frames, bindings and list cells, including the
+    /// strings in them.
+    pub live_bytes: usize,
+}
+
+#[derive(Default)]
+struct Heap {
+    frames: RefCell<Vec<Weak<RefCell<Frame>>>>,
+    stats: Cell<MemoryStats>,
+    live: Cell<usize>,
+    /// The account of the evaluator running on this thread.
+    account: RefCell<Option<Rc<Account>>>,
+    /// Registry size at which dead entries are pruned again.
+    prune_at: Cell<usize>,
+    /// Registry size at which `maybe_collect` runs a collection.
+    collect_at: Cell<usize>,
+}
+
+thread_local! {
+    static HEAP: Heap = Heap::default();
+}
+
+const MIN_PRUNE: usize = 1024;
+const MIN_COLLECT: usize = 4096;
+
+pub(super) fn register(frame: &Rc<RefCell<Frame>>) {
+    HEAP.with(|heap| {
+        let mut frames = heap.frames.borrow_mut();
+        frames.push(Rc::downgrade(frame));
+        if frames.len() >= heap.prune_at.get().max(MIN_PRUNE) {
+            frames.retain(|f| f.strong_count() > 0);
+            heap.prune_at.set(frames.len() * 2);
+        }
+    })
+}
+
+/// Bytes held by live frames, bindings and list cells on this thread.
+pub fn live() -> usize {
+    HEAP.with(|heap| heap.live.get())
+}
+
+pub fn stats() -> MemoryStats {
+    HEAP.with(|heap| {
+        let mut stats = heap.stats.get();
+        stats.live_bytes = heap.live.get();
+        stats.frames = heap
+            .frames
+            .borrow()
+            .iter()
+            .filter(|f| f.strong_count() > 0)
+            .count();
+        stats
+    })
+}
+
+/// Collects once the number of tracked frames has doubled since the last
+/// collection, so long-running hosts stay bounded without calling
+/// `collect` themselves.
+pub fn maybe_collect() -> usize {
+    let due =
+        HEAP.with(|heap| heap.frames.borrow().len() >= heap.collect_at.get().max(MIN_COLLECT));
+    if !due {
+        return 0;
+    }
+    let collected = collect();
+    HEAP.with(|heap| heap.collect_at.set(heap.frames.borrow().len() * 2));
+    collected
+}
+
+/// An object that can take part in a reference cycle. Cons cells are
+/// immutable and cannot form cycles themselves, but they are shared, so
+/// they are counted like frames to tell internal references from external
+/// ones.
+enum Node {
+    Frame(Rc<RefCell<Frame>>),
+    Cell(Rc<Cons>),
+}
+
+impl Node {
+    fn address(&self) -> usize {
+        match self {
+            Node::Frame(frame) => Rc::as_ptr(frame) as *const () as usize,
+            Node::Cell(cell) => Rc::as_ptr(cell) as *const () as usize,
+        }
+    }
+
+    fn strong_count(&self) -> usize {
+        match self {
+            Node::Frame(frame) => Rc::strong_count(frame),
+            Node::Cell(cell) => Rc::strong_count(cell),
+        }
+    }
+}
+
+/// Pushes the heap objects directly referenced by `value`.
+fn children(value: &Value, out: &mut Vec<Node>) {
+    match value {
+        Value::Func(func) => out.push(Node::Frame(func.env().frame.clone())),
+        Value::List(xs) => {
+            if let Some(cell) = xs.cell() {
+                out.push(Node::Cell(cell.clone()));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Frees frames that are only reachable through reference cycles.
+///
+/// Every reference between frames and the cons cells they hold is counted;
+/// an object whose strong count is higher is also referenced from outside
+/// (the host, the Rust stack or a value in flight) and is a root. Frames
+/// unreachable from the roots are cleared, which breaks their cycles.
+/// Returns the number of frames freed.
+pub fn collect() -> usize {
+    let mut nodes = HEAP.with(|heap| {
+        let mut frames = heap.frames.borrow_mut();
+        frames.retain(|f| f.strong_count() > 0);
+        heap.prune_at.set(frames.len() * 2);
+        frames
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(Node::Frame)
+            .collect::<Vec<_>>()
+    });
+    let mut index = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.address(), i))
+        .collect::<HashMap<_, _>>();
+
+    let mut edges = Vec::new();
+    let mut internal = vec![0; nodes.len()];
+    let mut roots = vec![false; nodes.len()];
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < nodes.len() {
+        match &nodes[i] {
+            Node::Frame(frame) => match frame.try_borrow() {
+                Ok(frame) => {
+                    if let Some(parent) = &frame.parent {
+                        found.push(Node::Frame(parent.frame.clone()));
+                    }
+                    frame.data.values().for_each(|v| children(v, &mut found));
+                }
+                // Being mutated right now, so it is in use.
+                Err(_) => roots[i] = true,
+            },
+            Node::Cell(cell) => {
+                children(&cell.car, &mut found);
+                children(&cell.cdr, &mut found);
+            }
+        }
+        let mut targets = Vec::new();
+        for node in found.drain(..) {
+            let j = *index.entry(node.address()).or_insert_with(|| {
+                nodes.push(node);
+                internal.push(0);
+                roots.push(false);
+                nodes.len() - 1
+            });
+            internal[j] += 1;
+            targets.push(j);
+        }
+        edges.push(targets);
+        i += 1;
+    }
+
+    // Our own reference to each node accounts for one strong count.
+    let mut reachable = vec![false; nodes.len()];
+    let mut stack = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        if roots[i] || node.strong_count() - 1 > internal[i] {
+            reachable[i] = true;
+            stack.push(i);
+        }
+    }
+    while let Some(i) = stack.pop() {
+        for &j in &edges[i] {
+            if !reachable[j] {
+                reachable[j] = true;
+                stack.push(j);
+            }
+        }
+    }
+
+    let mut garbage = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        if let (false, Node::Frame(frame)) = (reachable[i], node) {
+            let mut frame = frame.borrow_mut();
+            garbage.push((std::mem::take(&mut frame.data), frame.parent.take()));
+        }
+    }
+    let collected = garbage.len();
+    drop(garbage);
+    drop(nodes);
+
+    HEAP.with(|heap| {
+        let mut stats = heap.stats.get();
+        stats.collections += 1;
+        stats.last_collected = collected;
+        stats.total_collected += collected;
+        heap.stats.set(stats);
+    });
+    collected
+}
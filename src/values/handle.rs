@@ -0,0 +1,153 @@
+use std::{
+    any::Any,
+    cell::{Ref, RefCell, RefMut},
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    rc::Rc,
+};
+
+use crate::evaluator::{Arity, EvalError, EvalResult};
+
+use super::Value;
+
+type Method = Rc<dyn Fn(&mut dyn Any, &[Value]) -> EvalResult>;
+type Printer = Rc<dyn Fn(&dyn Any) -> String>;
+
+#[derive(Clone)]
+struct Vtable {
+    name: &'static str,
+    methods: HashMap<String, (Arity, Method)>,
+    printer: Option<Printer>,
+}
+
+/// Describes a host type exposed to scripts: its name, the methods that
+/// `(call handle :method ...)` can invoke and how it is printed.
+pub struct NativeType<T> {
+    vtable: Rc<Vtable>,
+    marker: PhantomData<T>,
+}
+
+impl<T: Any> NativeType<T> {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            vtable: Rc::new(Vtable {
+                name,
+                methods: HashMap::new(),
+                printer: None,
+            }),
+            marker: PhantomData,
+        }
+    }
+
+    /// Adds a method taking `arity` arguments, which are checked before
+    /// `method` runs.
+    pub fn method<F>(mut self, name: &str, arity: Arity, method: F) -> Self
+    where
+        F: Fn(&mut T, &[Value]) -> EvalResult + 'static,
+    {
+        let method: Method = Rc::new(move |object, args| match object.downcast_mut::<T>() {
+            Some(object) => method(object, args),
+            None => Err(EvalError::Native("handle has the wrong type".into())),
+        });
+        Rc::make_mut(&mut self.vtable)
+            .methods
+            .insert(name.to_string(), (arity, method));
+        self
+    }
+
+    pub fn printer<F>(mut self, printer: F) -> Self
+    where
+        F: Fn(&T) -> String + 'static,
+    {
+        let printer: Printer = Rc::new(move |object| match object.downcast_ref::<T>() {
+            Some(object) => printer(object),
+            None => String::new(),
+        });
+        Rc::make_mut(&mut self.vtable).printer = Some(printer);
+        self
+    }
+
+    /// Wraps `object` in a handle sharing this type's method table.
+    pub fn wrap(&self, object: T) -> Value {
+        let object: Rc<RefCell<dyn Any>> = Rc::new(RefCell::new(object));
+        Value::Native(Handle {
+            object,
+            vtable: self.vtable.clone(),
+        })
+    }
+}
+
+/// An opaque reference to a host object. Handles compare by identity.
+#[derive(Clone)]
+pub struct Handle {
+    object: Rc<RefCell<dyn Any>>,
+    vtable: Rc<Vtable>,
+}
+
+impl Handle {
+    pub fn type_name(&self) -> &'static str {
+        self.vtable.name
+    }
+
+    pub fn is<T: Any>(&self) -> bool {
+        self.object
+            .try_borrow()
+            .is_ok_and(|object| object.is::<T>())
+    }
+
+    /// `None` if the handle holds another type or is mutably borrowed.
+    pub fn borrow<T: Any>(&self) -> Option<Ref<'_, T>> {
+        let object = self.object.try_borrow().ok()?;
+        Ref::filter_map(object, |object| object.downcast_ref::<T>()).ok()
+    }
+
+    /// `None` if the handle holds another type or is already borrowed.
+    pub fn borrow_mut<T: Any>(&self) -> Option<RefMut<'_, T>> {
+        let object = self.object.try_borrow_mut().ok()?;
+        RefMut::filter_map(object, |object| object.downcast_mut::<T>()).ok()
+    }
+
+    pub fn call_method(&self, name: &str, args: &[Value]) -> EvalResult {
+        let (arity, method) = self
+            .vtable
+            .methods
+            .get(name)
+            .ok_or_else(|| EvalError::NoMethod {
+                type_name: self.type_name().into(),
+                method: name.into(),
+            })?;
+        if !arity.accepts(args.len()) {
+            return Err(EvalError::Arity {
+                name: name.into(),
+                expected: *arity,
+                got: args.len(),
+            });
+        }
+        let mut object = self.object.try_borrow_mut().map_err(|_| {
+            EvalError::Native(format!("{} handle is already in use", self.type_name()))
+        })?;
+        method(&mut *object, args)
+    }
+}
+
+impl PartialEq for Handle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.object, &other.object)
+    }
+}
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({})", self.type_name())
+    }
+}
+
+impl fmt::Display for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.vtable.printer, self.object.try_borrow()) {
+            (Some(printer), Ok(object)) => write!(f, "{}", printer(&*object)),
+            _ => write!(f, "#<{}>", self.type_name()),
+        }
+    }
+}
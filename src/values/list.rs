@@ -0,0 +1,288 @@
+use std::{fmt, iter::FromIterator, rc::Rc};
+
+use super::{gc, Span, Value};
+
+/// A persistent, singly linked list of cons cells. Cloning a list, taking
+/// its `rest` and `cons`ing onto it are O(1) and share structure.
+///
+/// A proper list ends in the empty list; a cell whose `cdr` is any other
+/// value makes the list improper, as in the dotted pair `(a . b)`.
+#[derive(Clone, Default)]
+pub struct List {
+    head: Option<Rc<Cons>>,
+}
+
+pub(super) struct Cons {
+    pub(super) car: Value,
+    pub(super) cdr: Value,
+    /// Where the reader found the list starting at this cell.
+    span: Option<Rc<Span>>,
+    charge: gc::Charge,
+}
+
+impl Cons {
+    fn new(car: Value, cdr: Value, span: Option<Rc<Span>>) -> Self {
+        let charge = gc::Charge::new(Self::bytes(&car, &cdr));
+        Self {
+            car,
+            cdr,
+            span,
+            charge,
+        }
+    }
+
+    fn bytes(car: &Value, cdr: &Value) -> usize {
+        gc::CELL_BYTES + gc::value_bytes(car) + gc::value_bytes(cdr)
+    }
+}
+
+impl Drop for Cons {
+    fn drop(&mut self) {
+        self.charge.release(Self::bytes(&self.car, &self.cdr));
+    }
+}
+
+impl List {
+    pub fn new() -> Self {
+        Self { head: None }
+    }
+
+    /// A cell holding `car` followed by `cdr`, which is usually another list.
+    pub fn cons(car: Value, cdr: Value) -> Self {
+        Self {
+            head: Some(Rc::new(Cons::new(car, cdr, None))),
+        }
+    }
+
+    /// Returns a new list with `value` in front of this one.
+    pub fn prepend(&self, value: Value) -> Self {
+        Self::cons(value, Value::List(self.clone()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn car(&self) -> Option<&Value> {
+        self.head.as_ref().map(|cell| &cell.car)
+    }
+
+    pub fn cdr(&self) -> Option<&Value> {
+        self.head.as_ref().map(|cell| &cell.cdr)
+    }
+
+    pub fn first(&self) -> Option<&Value> {
+        self.car()
+    }
+
+    /// Everything after the first element, empty for the empty list and for
+    /// the end of an improper list.
+    pub fn rest(&self) -> List {
+        match self.cdr() {
+            Some(Value::List(rest)) => rest.clone(),
+            _ => List::new(),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            cell: self.head.as_deref(),
+        }
+    }
+
+    /// Number of elements, not counting the tail of an improper list.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        self.iter().nth(index)
+    }
+
+    /// The final `cdr` of an improper list.
+    pub fn tail(&self) -> Option<&Value> {
+        let mut cell = self.head.as_deref()?;
+        loop {
+            match &cell.cdr {
+                Value::List(List { head: Some(next) }) => cell = next,
+                Value::List(List { head: None }) => return None,
+                tail => return Some(tail),
+            }
+        }
+    }
+
+    pub fn is_proper(&self) -> bool {
+        self.tail().is_none()
+    }
+
+    pub fn to_vec(&self) -> Vec<Value> {
+        self.iter().cloned().collect()
+    }
+
+    /// Builds an improper list ending in `tail` rather than the empty list,
+    /// `None` if there are no elements to hold it.
+    pub fn dotted(xs: Vec<Value>, tail: Value) -> Option<Self> {
+        let mut result = tail;
+        for x in xs.into_iter().rev() {
+            result = Value::List(List::cons(x, result));
+        }
+        match result {
+            Value::List(list) if !list.is_empty() => Some(list),
+            _ => None,
+        }
+    }
+
+    /// The source the list was read from, if it was read by a reader with
+    /// a source.
+    pub fn span(&self) -> Option<&Span> {
+        self.head.as_ref()?.span.as_deref()
+    }
+
+    /// Returns the list with its source set to `span`. The empty list has
+    /// no cell to hold it.
+    pub fn with_span(mut self, span: Span) -> Self {
+        if let Some(cell) = &mut self.head {
+            match Rc::get_mut(cell) {
+                Some(cell) => cell.span = Some(Rc::new(span)),
+                None => {
+                    *cell = Rc::new(Cons::new(
+                        cell.car.clone(),
+                        cell.cdr.clone(),
+                        Some(Rc::new(span)),
+                    ))
+                }
+            }
+        }
+        self
+    }
+
+    pub(super) fn cell(&self) -> Option<&Rc<Cons>> {
+        self.head.as_ref()
+    }
+}
+
+pub struct Iter<'a> {
+    cell: Option<&'a Cons>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Value;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cell = self.cell?;
+        self.cell = match &cell.cdr {
+            Value::List(rest) => rest.head.as_deref(),
+            _ => None,
+        };
+        Some(&cell.car)
+    }
+}
+
+impl<'a> IntoIterator for &'a List {
+    type Item = &'a Value;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Builds the list back to front, so it is a single pass over the vector.
+impl From<Vec<Value>> for List {
+    fn from(xs: Vec<Value>) -> Self {
+        let mut list = List::new();
+        for x in xs.into_iter().rev() {
+            list = List::cons(x, Value::List(list));
+        }
+        list
+    }
+}
+
+impl FromIterator<Value> for List {
+    fn from_iter<I: IntoIterator<Item = Value>>(iter: I) -> Self {
+        List::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl PartialEq for List {
+    fn eq(&self, other: &Self) -> bool {
+        let (mut a, mut b) = (self, other);
+        loop {
+            match (&a.head, &b.head) {
+                (None, None) => return true,
+                (Some(x), Some(y)) if Rc::ptr_eq(x, y) => return true,
+                (Some(x), Some(y)) if x.car == y.car => match (&x.cdr, &y.cdr) {
+                    (Value::List(xs), Value::List(ys)) => {
+                        a = xs;
+                        b = ys;
+                    }
+                    (x, y) => return x == y,
+                },
+                _ => return false,
+            }
+        }
+    }
+}
+
+impl fmt::Debug for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        list.entries(self.iter());
+        if let Some(tail) = self.tail() {
+            list.entry(&format_args!(". {:?}", tail));
+        }
+        list.finish()
+    }
+}
+
+impl fmt::Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, x) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            if f.alternate() {
+                write!(f, "{:#}", x)?;
+            } else {
+                write!(f, "{}", x)?;
+            }
+        }
+        match self.tail() {
+            Some(tail) if f.alternate() => write!(f, " . {:#}", tail)?,
+            Some(tail) => write!(f, " . {}", tail)?,
+            None => {}
+        }
+        write!(f, ")")
+    }
+}
+
+/// Unlinks the cells one at a time so dropping a long or deeply nested
+/// list does not recurse once per element. Nested lists no one else holds
+/// wait on a stack until the rest of their parent is freed.
+impl Drop for List {
+    fn drop(&mut self) {
+        let mut head = self.head.take();
+        let mut nested = Vec::new();
+        loop {
+            while let Some(cell) = head {
+                head = match Rc::try_unwrap(cell) {
+                    Ok(mut cell) => {
+                        if let Value::List(xs) = &mut cell.car {
+                            nested.extend(xs.head.take().filter(|c| Rc::strong_count(c) == 1));
+                        }
+                        match &mut cell.cdr {
+                            Value::List(rest) => rest.head.take(),
+                            _ => None,
+                        }
+                    }
+                    Err(_) => None,
+                };
+            }
+            match nested.pop() {
+                Some(cell) => head = Some(cell),
+                None => break,
+            }
+        }
+    }
+}
@@ -1,119 +1,373 @@
-use std::{
-    borrow::BorrowMut,
-    cell::RefCell,
-    collections::{HashMap, HashSet},
-    ops::Index,
-    rc::Rc,
-};
+use std::{cell::RefCell, fmt, rc::Rc};
+
+mod convert;
+pub mod gc;
+mod handle;
+mod list;
+mod span;
+mod symbol;
+
+pub use convert::{FromValue, IntoValue};
+pub use handle::{Handle, NativeType};
+pub use list::List;
+pub use span::{Source, Span};
+pub use symbol::{Symbol, SymbolHasher, SymbolMap};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Value {
     None,
     Num(f64),
     Str(String),
-    Sym(String),
+    Sym(Symbol),
     Atom(String),
     Bool(bool),
-    List(Vec<Value>),
+    List(List),
     Func(OwlFunc),
+    /// A built-in function of the evaluator, referred to by name.
+    Intrinsic(Symbol),
+    Native(Handle),
+    /// An error caught by `try` or made with `error`.
+    Error(Rc<OwlError>),
 }
 
 impl Value {
-    pub fn is_true(self) -> bool {
+    pub fn is_true(&self) -> bool {
         match self {
             Value::None => false,
-            Value::Num(n) if n != 0.0 => true,
-            Value::Bool(b) => b,
+            Value::Num(n) if *n != 0.0 => true,
+            Value::Bool(b) => *b,
             _ => true,
         }
     }
 
-    pub fn as_num(self) -> f64 {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::None => "none",
+            Value::Num(_) => "number",
+            Value::Str(_) => "string",
+            Value::Sym(_) => "symbol",
+            Value::Atom(_) => "atom",
+            Value::Bool(_) => "boolean",
+            Value::List(_) => "list",
+            Value::Func(_) | Value::Intrinsic(_) => "function",
+            Value::Native(handle) => handle.type_name(),
+            Value::Error(_) => "error",
+        }
+    }
+
+    /// The number this is, `None` for any other type.
+    pub fn as_num(&self) -> Option<f64> {
         match self {
-            Value::Num(f) => f,
-            _ => 0.0,
+            Value::Num(f) => Some(*f),
+            _ => None,
         }
     }
 
     pub fn as_vec(self) -> Vec<Value> {
         match self {
-            Value::List(xs) => xs,
+            Value::List(xs) => xs.to_vec(),
             _ => vec![],
         }
     }
+
+    /// Wraps a vector of values as a list.
+    pub fn list(xs: Vec<Value>) -> Value {
+        Value::List(List::from(xs))
+    }
+}
+
+pub fn cons(car: Value, cdr: Value) -> Value {
+    Value::List(List::cons(car, cdr))
 }
 
 pub fn car(v: &Value) -> &Value {
     match v {
-        Value::List(xs) => &xs[0],
+        Value::List(xs) => xs.car().unwrap_or(&Value::None),
         _ => &Value::None,
     }
 }
 
 pub fn cdr(v: &Value) -> Value {
     match v {
-        Value::List(xs) => Value::List(xs[1..].to_vec()),
+        Value::List(xs) => xs.cdr().cloned().unwrap_or(Value::None),
         _ => Value::None,
     }
 }
 
-impl ToString for Value {
-    fn to_string(&self) -> String {
+/// The alternate form, `{:#}`, shows strings quoted and escaped as they
+/// are written in scripts, including those inside lists.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Value::None => todo!(),
-            Value::Num(n) => n.to_string(),
-            Value::Str(s) => s.to_string(),
-            Value::Sym(s) => s.to_string(),
-            Value::Atom(a) => a.to_string(),
-            Value::Bool(t) => {
-                if *t {
-                    "#t".to_string()
-                } else {
-                    "#f".to_string()
+            Value::None => write!(f, "none"),
+            Value::Num(n) => write!(f, "{}", n),
+            Value::Str(s) if f.alternate() => {
+                write!(f, "\"")?;
+                for c in s.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\t' => write!(f, "\\t")?,
+                        c => write!(f, "{}", c)?,
+                    }
                 }
+                write!(f, "\"")
             }
-            Value::List(_) => todo!(),
-            Value::Func(_) => todo!(),
+            Value::Str(s) => write!(f, "{}", s),
+            Value::Sym(s) => write!(f, "{}", s),
+            Value::Atom(a) => write!(f, ":{}", a),
+            Value::Bool(t) => write!(f, "{}", if *t { "#t" } else { "#f" }),
+            Value::List(xs) if f.alternate() => write!(f, "{:#}", xs),
+            Value::List(xs) => write!(f, "{}", xs),
+            Value::Func(func) => match func.name() {
+                Some(name) => write!(f, "<fun {}>", name),
+                None => write!(f, "<fun>"),
+            },
+            Value::Intrinsic(name) => write!(f, "<intrinsic {}>", name),
+            Value::Native(handle) => write!(f, "{}", handle),
+            Value::Error(e) => write!(f, "<error :{} {}>", e.kind(), e.message()),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// A shared, reference-counted environment frame. Cloning an `Env` yields
+/// another handle to the same frame, so closures, nested scopes and the
+/// global scope all observe each other's updates.
+#[derive(Clone)]
 pub struct Env {
-    data: HashMap<String, Value>,
-    parent: Option<Box<Env>>,
+    frame: Rc<RefCell<Frame>>,
+}
+
+struct Frame {
+    data: SymbolMap<Value>,
+    parent: Option<Env>,
+    charge: gc::Charge,
+    /// Bytes charged for the frame and its bindings.
+    held: usize,
+}
+
+impl Frame {
+    /// Binds `ident`, charging only for what the binding adds.
+    fn bind(&mut self, ident: Symbol, value: Value) {
+        let added = gc::value_bytes(&value);
+        match self.data.insert(ident, value) {
+            Some(old) => self.recharge(added, gc::value_bytes(&old)),
+            None => self.recharge(gc::BINDING_BYTES + added, 0),
+        }
+    }
+
+    fn recharge(&mut self, added: usize, freed: usize) {
+        self.charge.adopt(self.held);
+        self.charge.add(added);
+        self.charge.release(freed);
+        self.held = self.held + added - freed;
+    }
+}
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        self.charge.release(self.held);
+    }
+}
+
+impl Default for Env {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Env {
     pub fn new() -> Self {
-        Self {
-            data: HashMap::new(),
-            parent: None,
-        }
+        Self::with_parent(None)
+    }
+
+    /// Creates a new frame whose parent is this one.
+    pub fn child(&self) -> Self {
+        Self::with_parent(Some(self.clone()))
+    }
+
+    fn with_parent(parent: Option<Env>) -> Self {
+        let frame = Rc::new(RefCell::new(Frame {
+            data: SymbolMap::default(),
+            parent,
+            charge: gc::Charge::new(gc::FRAME_BYTES),
+            held: gc::FRAME_BYTES,
+        }));
+        gc::register(&frame);
+        Self { frame }
+    }
+
+    /// Whether `ident` is bound in this frame, ignoring parents.
+    pub fn has<T: Into<Symbol>>(&self, ident: T) -> bool {
+        self.frame.borrow().data.contains_key(&ident.into())
+    }
+
+    /// Looks `ident` up in this frame only.
+    pub fn find_local<T: Into<Symbol>>(&self, ident: T) -> Option<Value> {
+        self.frame.borrow().data.get(&ident.into()).cloned()
     }
 
-    pub fn has<T: ToString>(self: &mut Self, ident: T) -> bool {
-        self.data.contains_key(&ident.to_string())
+    /// The names bound in this frame.
+    pub fn names(&self) -> Vec<Symbol> {
+        self.frame.borrow().data.keys().copied().collect()
     }
 
-    pub fn set<T: ToString>(self: &mut Self, ident: T, value: Value) {
-        self.data.insert(ident.to_string(), value);
+    /// Binds `ident` in this frame.
+    pub fn set<T: Into<Symbol>>(&self, ident: T, value: Value) {
+        self.frame.borrow_mut().bind(ident.into(), value);
     }
 
-    pub fn get(self: &Self, ident: String) -> &Value {
-        if self.data.contains_key(&ident) {
-            return self.data.get(&ident).unwrap_or(&Value::None);
+    /// Updates the nearest existing binding of `ident`, returning false if
+    /// it is unbound.
+    pub fn assign<T: Into<Symbol>>(&self, ident: T, value: Value) -> bool {
+        let ident = ident.into();
+        let mut frame = self.frame.borrow_mut();
+        if frame.data.contains_key(&ident) {
+            frame.bind(ident, value);
+            return true;
+        }
+        match &frame.parent {
+            Some(parent) => parent.assign(ident, value),
+            None => false,
         }
-        match &self.parent {
-            Some(env) => env.get(ident),
-            None => &Value::None,
+    }
+
+    /// Looks `ident` up through the parent chain, `None` if it is unbound.
+    pub fn find<T: Into<Symbol>>(&self, ident: T) -> Option<Value> {
+        self.lookup(ident.into())
+    }
+
+    fn lookup(&self, ident: Symbol) -> Option<Value> {
+        let frame = self.frame.borrow();
+        match frame.data.get(&ident) {
+            Some(value) => Some(value.clone()),
+            None => frame.parent.as_ref().and_then(|env| env.lookup(ident)),
+        }
+    }
+
+    pub fn get<T: Into<Symbol>>(&self, ident: T) -> Value {
+        self.find(ident).unwrap_or(Value::None)
+    }
+
+    pub fn parent(&self) -> Option<Env> {
+        self.frame.borrow().parent.clone()
+    }
+
+    /// The outermost frame of the parent chain.
+    pub fn root(&self) -> Env {
+        match self.parent() {
+            Some(parent) => parent.root(),
+            None => self.clone(),
         }
     }
+
+    pub fn ptr_eq(&self, other: &Env) -> bool {
+        Rc::ptr_eq(&self.frame, &other.frame)
+    }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// Environments compare by identity, frames may refer back to themselves.
+impl PartialEq for Env {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl fmt::Debug for Env {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = self
+            .frame
+            .borrow()
+            .data
+            .keys()
+            .map(Symbol::as_str)
+            .collect::<Vec<_>>();
+        names.sort();
+        f.debug_struct("Env")
+            .field("names", &names)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone)]
 pub struct OwlFunc {
+    name: Option<String>,
     params: Rc<Value>,
     body: Rc<Value>,
-    env: Rc<RefCell<Env>>,
+    env: Env,
+}
+
+impl OwlFunc {
+    pub fn new(name: Option<String>, params: Value, body: Value, env: Env) -> Self {
+        Self {
+            name,
+            params: Rc::new(params),
+            body: Rc::new(body),
+            env,
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn params(&self) -> &Value {
+        &self.params
+    }
+
+    pub fn body(&self) -> &Value {
+        &self.body
+    }
+
+    pub fn env(&self) -> &Env {
+        &self.env
+    }
+}
+
+/// Functions are compared by identity, their environment may refer back to them.
+impl PartialEq for OwlFunc {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.body, &other.body) && self.env.ptr_eq(&other.env)
+    }
+}
+
+impl fmt::Debug for OwlFunc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwlFunc")
+            .field("name", &self.name)
+            .field("params", &self.params)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An error value with a kind such as `type` or `unbound`, a message and a
+/// payload of any value. Errors compare by value.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OwlError {
+    kind: String,
+    message: String,
+    payload: Value,
+}
+
+impl OwlError {
+    pub fn new<K: Into<String>, M: Into<String>>(kind: K, message: M, payload: Value) -> Self {
+        Self {
+            kind: kind.into(),
+            message: message.into(),
+            payload,
+        }
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn payload(&self) -> &Value {
+        &self.payload
+    }
 }
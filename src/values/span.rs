@@ -0,0 +1,59 @@
+use std::{fmt, rc::Rc};
+
+/// The text of a script and the name it is reported under, usually its
+/// path.
+#[derive(Debug, PartialEq)]
+pub struct Source {
+    pub name: String,
+    pub text: String,
+}
+
+impl Source {
+    pub fn new<N: Into<String>, T: Into<String>>(name: N, text: T) -> Rc<Self> {
+        Rc::new(Self {
+            name: name.into(),
+            text: text.into(),
+        })
+    }
+
+    /// The 1-based line and column of byte offset `offset`, counting
+    /// columns in characters.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let before = &self.text[..offset.min(self.text.len())];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (line, before[line_start..].chars().count() + 1)
+    }
+}
+
+/// The bytes `start..end` of a source that a form was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub source: Rc<Source>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// The 1-based line and column where the span starts.
+    pub fn line_col(&self) -> (usize, usize) {
+        self.source.line_col(self.start)
+    }
+
+    /// The 1-based line and column just past the end of the span.
+    pub fn end_line_col(&self) -> (usize, usize) {
+        self.source.line_col(self.end)
+    }
+
+    pub fn text(&self) -> &str {
+        &self.source.text[self.start..self.end]
+    }
+}
+
+/// Formats as `name:line:column`.
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line, column) = self.line_col();
+        write!(f, "{}:{}:{}", self.source.name, line, column)
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    hash::{BuildHasherDefault, Hasher},
+    sync::{Mutex, OnceLock},
+};
+
+/// An interned symbol name. Symbols are small integer IDs, so comparing and
+/// hashing them never touches the string.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+#[derive(Clone, Copy)]
+struct Entry {
+    name: &'static str,
+    /// The namespace and name of a qualified symbol.
+    parts: Option<(Symbol, Symbol)>,
+}
+
+struct Interner {
+    entries: Vec<Entry>,
+    ids: HashMap<&'static str, Symbol>,
+}
+
+thread_local! {
+    /// This thread's copy of the interned entries, so that reading a name
+    /// takes the interner's lock only the first time a new symbol is seen.
+    static ENTRIES: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Special forms, interned first so their IDs are known constants.
+const KEYWORDS: [&str; 12] = [
+    "do", "if", "def", "set", "fun", "fn", "quote", "and", "or", "module", "import", "try",
+];
+
+impl Symbol {
+    pub const DO: Symbol = Symbol(0);
+    pub const IF: Symbol = Symbol(1);
+    pub const DEF: Symbol = Symbol(2);
+    pub const SET: Symbol = Symbol(3);
+    pub const FUN: Symbol = Symbol(4);
+    pub const FN: Symbol = Symbol(5);
+    pub const QUOTE: Symbol = Symbol(6);
+    pub const AND: Symbol = Symbol(7);
+    pub const OR: Symbol = Symbol(8);
+    pub const MODULE: Symbol = Symbol(9);
+    pub const IMPORT: Symbol = Symbol(10);
+    pub const TRY: Symbol = Symbol(11);
+
+    /// Interns `name`. Interned names are never freed, so a host whose
+    /// scripts keep making new names, for example from arbitrary strings,
+    /// grows for as long as it runs.
+    pub fn new(name: &str) -> Self {
+        interner().lock().unwrap().get_or_intern(name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.entry().name
+    }
+
+    /// The namespace and name of a qualified symbol such as `math/sqrt` or
+    /// `str.split`, split at the last `/` or `.`. Both are reserved as
+    /// separators: any name containing one, such as `file.txt`, is taken
+    /// as qualified, though a binding of the whole name still wins.
+    pub fn qualified(&self) -> Option<(Symbol, Symbol)> {
+        self.entry().parts
+    }
+
+    fn entry(&self) -> Entry {
+        let id = self.0 as usize;
+        let cached = ENTRIES.try_with(|entries| {
+            if let Some(&entry) = entries.borrow().get(id) {
+                return entry;
+            }
+            let interner = interner().lock().unwrap();
+            let mut entries = entries.borrow_mut();
+            let seen = entries.len();
+            entries.extend_from_slice(&interner.entries[seen..]);
+            entries[id]
+        });
+        // The copy is gone while the thread shuts down.
+        cached.unwrap_or_else(|_| interner().lock().unwrap().entries[id])
+    }
+}
+
+impl Interner {
+    fn get_or_intern(&mut self, name: &str) -> Symbol {
+        match self.ids.get(name) {
+            Some(&sym) => sym,
+            None => self.intern(Box::leak(name.into())),
+        }
+    }
+
+    fn intern(&mut self, name: &'static str) -> Symbol {
+        let parts = match name.rfind(['/', '.']) {
+            Some(i) if i > 0 && i + 1 < name.len() => Some((
+                self.get_or_intern(&name[..i]),
+                self.get_or_intern(&name[i + 1..]),
+            )),
+            _ => None,
+        };
+        let sym = Symbol(self.entries.len() as u32);
+        self.entries.push(Entry { name, parts });
+        self.ids.insert(name, sym);
+        sym
+    }
+}
+
+fn interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(|| {
+        let mut interner = Interner {
+            entries: Vec::new(),
+            ids: HashMap::new(),
+        };
+        for keyword in KEYWORDS {
+            interner.intern(keyword);
+        }
+        Mutex::new(interner)
+    })
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol::new(name)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(name: &String) -> Self {
+        Symbol::new(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        Symbol::new(&name)
+    }
+}
+
+impl From<&Symbol> for Symbol {
+    fn from(sym: &Symbol) -> Self {
+        *sym
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Symbol {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+/// Hashes symbols by their ID; used for tables keyed by `Symbol`.
+#[derive(Default)]
+pub struct SymbolHasher(u64);
+
+impl Hasher for SymbolHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 << 8 | b as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        }
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.0 = (n as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    }
+}
+
+pub type SymbolMap<V> = HashMap<Symbol, V, BuildHasherDefault<SymbolHasher>>;
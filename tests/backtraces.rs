@@ -0,0 +1,133 @@
+use std::{env, fs, process};
+
+use owl::{
+    evaluator::{EvalError, Evaluator},
+    values::{Env, Value},
+};
+
+#[test]
+fn errors_record_the_calls_they_unwind_through() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    let code = "(fun inner (x)\n  (sqrt x))\n(fun outer (a b)\n  (inner b))\n(outer 1 :b)";
+    assert!(matches!(
+        evaluator.eval(&env, code),
+        Err(EvalError::Type { .. })
+    ));
+    let backtrace = evaluator.backtrace();
+    let frames = backtrace
+        .frames
+        .iter()
+        .map(|frame| frame.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        frames,
+        [
+            "<eval>:2:3: (sqrt :b)",
+            "<eval>:4:3: (inner :b)",
+            "<eval>:5:1: (outer 1 :b)",
+        ]
+    );
+    assert_eq!(
+        backtrace.to_string(),
+        "traceback (most recent call last):\n  \
+         <eval>:5:1: (outer 1 :b)\n  \
+         <eval>:4:3: (inner :b)\n  \
+         <eval>:2:3: (sqrt :b)\n"
+    );
+    assert_eq!(backtrace.frames[0].name, "sqrt");
+    assert_eq!(
+        backtrace.frames[0].span.as_ref().unwrap().text(),
+        "(sqrt x)"
+    );
+}
+
+#[test]
+fn successful_evaluation_clears_the_backtrace() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    assert!(evaluator.eval(&env, "(car 1 2)").is_err());
+    assert!(!evaluator.backtrace().is_empty());
+    evaluator.eval(&env, "(car (list 1))").unwrap();
+    assert!(evaluator.backtrace().is_empty());
+}
+
+#[test]
+fn caught_errors_leave_no_frames_behind() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    let code = r#"
+        (fun fail () (throw :first))
+        (try (fail) (catch e none))
+        (fun again () (car))
+        (again)
+    "#;
+    assert!(evaluator.eval(&env, code).is_err());
+    let names = evaluator
+        .backtrace()
+        .frames
+        .iter()
+        .map(|frame| frame.name.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["car", "again"]);
+}
+
+#[test]
+fn long_arguments_are_truncated() {
+    let evaluator = Evaluator::new();
+    let code = "(fun f (xs) (car)) (f (range 100))";
+    assert!(evaluator.eval(&Env::new(), code).is_err());
+    let args = &evaluator.backtrace().frames[1].args;
+    assert!(args.starts_with("(0 1 2 3"));
+    assert!(args.ends_with("..."));
+    assert_eq!(args.chars().count(), 63);
+}
+
+#[test]
+fn deep_recursion_keeps_only_the_innermost_frames() {
+    let evaluator = Evaluator::new();
+    evaluator.set_backtrace_limit(5);
+    let code = "(fun f (n) (if (= n 0) (car) (f (- n 1)))) (f 20)";
+    assert!(evaluator.eval(&Env::new(), code).is_err());
+    let backtrace = evaluator.backtrace();
+    assert_eq!(backtrace.frames.len(), 5);
+    assert_eq!(backtrace.omitted, 17);
+    assert_eq!(backtrace.frames[1].args, "0");
+    assert!(backtrace.to_string().contains("  ... 17 earlier calls\n"));
+}
+
+#[test]
+fn frames_name_the_file_they_were_read_from() {
+    let dir = env::temp_dir().join(format!("owl-{}-backtrace", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("lib.owl"),
+        "(module lib (export check)\n  (fun check (x) (sqrt x)))",
+    )
+    .unwrap();
+    let main = dir.join("main.owl");
+    fs::write(&main, "(import \"lib.owl\")\n(check :x)").unwrap();
+
+    let evaluator = Evaluator::new();
+    assert!(evaluator.eval_file(&Env::new(), &main).is_err());
+    let spans = evaluator
+        .backtrace()
+        .frames
+        .iter()
+        .map(|frame| frame.span.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert!(spans[0].ends_with("lib.owl:2:18"), "{:?}", spans);
+    assert!(spans[1].ends_with("main.owl:2:1"), "{:?}", spans);
+}
+
+#[test]
+fn string_arguments_are_quoted() {
+    let evaluator = Evaluator::new();
+    let code = r#"(fun f (s xs) (car)) (f "a b" (list "c" 1))"#;
+    assert!(evaluator.eval(&Env::new(), code).is_err());
+    assert_eq!(evaluator.backtrace().frames[1].args, r#""a b" ("c" 1)"#);
+
+    let quoted = Value::Str("say \"hi\"\n\\".into());
+    assert_eq!(format!("{:#}", quoted), r#""say \"hi\"\n\\""#);
+    assert_eq!(quoted.to_string(), "say \"hi\"\n\\");
+}
@@ -0,0 +1,168 @@
+use owl::{
+    diagnostics::{Diagnostic, Severity},
+    evaluator::{Capabilities, EvalError, Evaluator},
+    reader::ReaderError,
+    values::{Env, Source, Span},
+};
+
+fn diagnose(evaluator: &Evaluator, code: &str) -> String {
+    let error = evaluator.eval(&Env::new(), code).unwrap_err();
+    evaluator.diagnose(&error).render(false)
+}
+
+#[test]
+fn errors_point_at_the_form_they_were_raised_in() {
+    let evaluator = Evaluator::new();
+    let code = "(fun inner (x)\n  (sqrt x))\n(inner :b)";
+    assert_eq!(
+        diagnose(&evaluator, code),
+        "error[E0002]: sqrt: argument 1 expected number but got atom\n \
+         --> <eval>:2:3\n  \
+         |\n\
+         2 |   (sqrt x))\n  \
+         |   ^^^^^^^^ expected number, found atom\n"
+    );
+}
+
+#[test]
+fn unbalanced_lists_show_where_they_were_opened() {
+    let evaluator = Evaluator::new();
+    let error = evaluator
+        .eval(&Env::new(), "(def x 1)\n(list 1\n  (+ 2 3)\n")
+        .unwrap_err();
+    let diagnostic = evaluator.diagnose(&error);
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(diagnostic.related[0].span.line_col(), (2, 1));
+    assert_eq!(
+        diagnostic.render(false),
+        "error[R0007]: unbalanced parenthesis\n \
+         --> <eval>:3:10\n  \
+         |\n\
+         2 | (list 1\n  \
+         | - opened here\n\
+         3 |   (+ 2 3)\n  \
+         |          ^ expected `)`\n  \
+         |\n  \
+         = help: every `(` needs a matching `)`\n"
+    );
+}
+
+#[test]
+fn diagnostics_suggest_fixes() {
+    let evaluator = Evaluator::with_capabilities(Capabilities::none());
+    let rendered = diagnose(&evaluator, "(print 1)");
+    assert!(
+        rendered.ends_with("= help: the evaluator was created without the console capability\n")
+    );
+
+    let evaluator = Evaluator::new();
+    let rendered = diagnose(&evaluator, "(throw 1)");
+    assert!(rendered.starts_with("error[E0017]: uncaught exception: 1\n"));
+    assert!(rendered.contains("^^^^^^^^^ thrown here"));
+}
+
+#[test]
+fn errors_without_a_source_have_no_snippet() {
+    let evaluator = Evaluator::new();
+    let error = EvalError::Native("boom".into());
+    assert_eq!(
+        evaluator.diagnose(&error).render(false),
+        "error[E0007]: boom\n"
+    );
+}
+
+#[test]
+fn rendering_can_use_colors() {
+    let evaluator = Evaluator::new();
+    let error = evaluator.eval(&Env::new(), "(car 1 2)").unwrap_err();
+    let rendered = evaluator.diagnose(&error).render(true);
+    assert!(rendered.starts_with("\x1b[1;31merror[E0001]\x1b[0m"));
+    assert!(rendered.contains("\x1b[1;31m^^^^^^^^^"));
+}
+
+#[test]
+fn error_kinds_have_stable_codes() {
+    assert_eq!(ReaderError::UnterminatedString.code(), "R0006");
+    assert_eq!(
+        EvalError::Reader(ReaderError::UnbalancedBraces).code(),
+        "R0008"
+    );
+    assert_eq!(
+        EvalError::Unbound {
+            name: "x".into(),
+            suggestions: vec![],
+        }
+        .code(),
+        "E0004"
+    );
+    assert_eq!(EvalError::OutOfFuel.code(), "E0010");
+}
+
+#[test]
+fn diagnostics_serialize_to_json() {
+    let evaluator = Evaluator::new();
+    let error = evaluator
+        .eval(&Env::new(), "(list 1\n  (+ 2 3)\n")
+        .unwrap_err();
+    assert_eq!(
+        evaluator.diagnose(&error).to_json(),
+        r#"{"severity":"error","code":"R0007","message":"unbalanced parenthesis","#.to_owned()
+            + r#""file":"<eval>","range":{"start":{"line":2,"column":10},"end":{"line":2,"column":10}},"#
+            + r#""label":"expected `)`","related":[{"file":"<eval>","#
+            + r#""range":{"start":{"line":1,"column":1},"end":{"line":1,"column":2}},"#
+            + r#""message":"opened here"}],"help":"every `(` needs a matching `)`"}"#
+    );
+}
+
+#[test]
+fn json_strings_are_escaped() {
+    let source = Source::new("dir\\a \"b\".owl", "(x)");
+    let span = Span {
+        source,
+        start: 0,
+        end: 3,
+    };
+    let diagnostic = Diagnostic::error("line\n\tbreak\u{1}").with_primary(span, "");
+    assert_eq!(
+        diagnostic.to_json(),
+        r#"{"severity":"error","code":null,"message":"line\n\tbreak\u0001","#.to_owned()
+            + r#""file":"dir\\a \"b\".owl","range":{"start":{"line":1,"column":1},"#
+            + r#""end":{"line":1,"column":4}},"label":"","related":[],"help":null}"#
+    );
+}
+
+#[test]
+fn reading_checks_scripts_without_running_them() {
+    let dir = std::env::temp_dir().join(format!("owl-check-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let good = dir.join("good.owl");
+    let bad = dir.join("bad.owl");
+    std::fs::write(&good, "(print (undefined))").unwrap();
+    std::fs::write(&bad, "(print (+ 1 2)").unwrap();
+
+    let evaluator = Evaluator::new();
+    assert_eq!(evaluator.check_file(&good), Ok(()));
+    let error = evaluator.check_file(&bad).unwrap_err();
+    assert_eq!(error.code(), "R0007");
+    let diagnostic = evaluator.diagnose(&error);
+    assert_eq!(
+        diagnostic.primary.unwrap().span.source.name,
+        bad.display().to_string()
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unbound_names_are_underlined_where_they_are_used() {
+    let evaluator = Evaluator::new();
+    assert_eq!(
+        diagnose(&evaluator, "(def count 2)\n(print (+ count cout 1))"),
+        "error[E0004]: cout is not defined\n \
+         --> <eval>:2:17\n  \
+         |\n\
+         2 | (print (+ count cout 1))\n  \
+         |                 ^^^^ not defined\n  \
+         |\n  \
+         = help: did you mean `count`?\n"
+    );
+}
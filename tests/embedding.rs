@@ -0,0 +1,78 @@
+use owl::{
+    evaluator::{EvalError, Evaluator},
+    values::{Env, Value},
+};
+
+#[test]
+fn calling_script_functions_from_rust() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(
+            &env,
+            r#"
+            (fun on-event (name payload)
+              (if (= name "click") (* payload 2) 0))
+            (fun fact (n) (if (= n 0) 1 (* n (fact (- n 1)))))
+            "#,
+        )
+        .unwrap();
+
+    let doubled: f64 = evaluator.call(&env, "on-event", ("click", 21.0)).unwrap();
+    assert_eq!(doubled, 42.0);
+    let ignored: i64 = evaluator.call(&env, "on-event", ("key", 1.0)).unwrap();
+    assert_eq!(ignored, 0);
+    let fact: i64 = evaluator.call(&env, "fact", (5i64,)).unwrap();
+    assert_eq!(fact, 120);
+    let sum: f64 = evaluator
+        .call(&env, "+", vec![Value::Num(1.0), Value::Num(2.0)])
+        .unwrap();
+    assert_eq!(sum, 3.0);
+}
+
+#[test]
+fn calling_reports_typed_errors() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(&env, r#"(fun greet (name) "hello") (def x 1)"#)
+        .unwrap();
+
+    assert_eq!(
+        evaluator.call::<f64, _>(&env, "greet", ("owl",)),
+        Err(EvalError::ReturnType {
+            name: "greet".into(),
+            expected: "number".into(),
+            got: "string".into(),
+        })
+    );
+    assert_eq!(
+        evaluator.call::<Value, _>(&env, "missing", ()),
+        Err(EvalError::Unbound {
+            name: "missing".into(),
+            suggestions: vec![],
+        })
+    );
+    assert_eq!(
+        evaluator.call::<Value, _>(&env, "x", ()),
+        Err(EvalError::NotCallable("number".into()))
+    );
+    assert!(matches!(
+        evaluator.call::<Value, _>(&env, "greet", ()),
+        Err(EvalError::Arity { got: 0, .. })
+    ));
+}
+
+#[test]
+fn anonymous_functions() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    assert_eq!(
+        evaluator.eval(&env, "((fn (a b) (- a b)) 10 4)"),
+        Ok(Value::Num(6.0))
+    );
+    assert_eq!(
+        evaluator.eval(&env, "(def twice (fn (x) (* x 2))) (twice 8)"),
+        Ok(Value::Num(16.0))
+    );
+}
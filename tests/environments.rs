@@ -0,0 +1,158 @@
+use owl::{
+    evaluator::{EvalError, Evaluator},
+    values::{
+        Env,
+        Value::{self, Bool, Num},
+    },
+};
+
+#[test]
+fn counter_closures_keep_their_own_state() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(
+            &env,
+            r#"
+            (fun make-counter ()
+              (def n 0)
+              (fn () (set n (+ n 1))))
+            (def a (make-counter))
+            (def b (make-counter))
+            (a) (a) (b)
+            "#,
+        )
+        .unwrap();
+    assert_eq!(evaluator.eval(&env, "(a)"), Ok(Num(3.0)));
+    assert_eq!(evaluator.eval(&env, "(b)"), Ok(Num(2.0)));
+}
+
+#[test]
+fn closures_sharing_a_frame_see_each_others_updates() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(
+            &env,
+            r#"
+            (fun make-account ()
+              (def balance 0)
+              (def deposit (fn (x) (set balance (+ balance x))))
+              (fn (msg x) (if (= msg :deposit) (deposit x) balance)))
+            (def account (make-account))
+            (account :deposit 10)
+            (account :deposit 5)
+            "#,
+        )
+        .unwrap();
+    assert_eq!(evaluator.eval(&env, "(account :balance 0)"), Ok(Num(15.0)));
+}
+
+#[test]
+fn functions_update_the_global_scope() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(
+            &env,
+            r#"
+            (def total 0)
+            (fun add (x) (set total (+ total x)))
+            (add 4) (add 6)
+            "#,
+        )
+        .unwrap();
+    assert_eq!(env.get("total"), Num(10.0));
+
+    env.set("total", Num(100.0));
+    assert_eq!(evaluator.eval(&env, "(add 1)"), Ok(Num(101.0)));
+    assert_eq!(
+        evaluator.eval(&env, "(set missing 1)"),
+        Err(EvalError::Unbound {
+            name: "missing".into(),
+            suggestions: vec![],
+        })
+    );
+}
+
+#[test]
+fn mutual_recursion() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(
+            &env,
+            r#"
+            (fun even? (n) (if (= n 0) #t (odd? (- n 1))))
+            (fun odd? (n) (if (= n 0) #f (even? (- n 1))))
+            "#,
+        )
+        .unwrap();
+    assert_eq!(evaluator.eval(&env, "(even? 10)"), Ok(Bool(true)));
+    assert_eq!(evaluator.eval(&env, "(odd? 7)"), Ok(Bool(true)));
+    assert_eq!(evaluator.eval(&env, "(even? 7)"), Ok(Bool(false)));
+}
+
+#[test]
+fn function_parameters_shadow_outer_bindings() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(&env, "(def x 1) (fun shadow (x) (set x 5) x)")
+        .unwrap();
+    assert_eq!(evaluator.eval(&env, "(shadow 2)"), Ok(Num(5.0)));
+    assert_eq!(evaluator.eval(&env, "x"), Ok(Num(1.0)));
+}
+
+#[test]
+fn unbound_symbols_suggest_similar_names() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.eval(&env, "(def count 3)").unwrap();
+    assert_eq!(
+        evaluator.eval(&env, "(+ cout 1)"),
+        Err(EvalError::Unbound {
+            name: "cout".into(),
+            suggestions: vec!["count".into()],
+        })
+    );
+    // Names from enclosing scopes and intrinsics are suggested too.
+    assert_eq!(
+        evaluator.eval(&env, "((fn (total) (+ totl 1)) 2)"),
+        Err(EvalError::Unbound {
+            name: "totl".into(),
+            suggestions: vec!["total".into()],
+        })
+    );
+    assert!(matches!(
+        evaluator.eval(&env, "(lenght (list 1))"),
+        Err(EvalError::Unbound { suggestions, .. }) if suggestions == ["length"]
+    ));
+    assert!(matches!(
+        evaluator.eval(&env, "zzzzzz"),
+        Err(EvalError::Unbound { suggestions, .. }) if suggestions.is_empty()
+    ));
+
+    let error = evaluator.eval(&env, "cout").unwrap_err();
+    assert_eq!(
+        evaluator.diagnose(&error).help.as_deref(),
+        Some("did you mean `count`?")
+    );
+}
+
+#[test]
+fn lenient_lookup_evaluates_unbound_symbols_to_none() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.set_lenient_lookup(true);
+    assert_eq!(evaluator.eval(&env, "missing"), Ok(Value::None));
+    assert!(matches!(
+        evaluator.eval(&env, "(missing)"),
+        Err(EvalError::Unbound { .. })
+    ));
+    evaluator.set_lenient_lookup(false);
+    assert!(matches!(
+        evaluator.eval(&env, "missing"),
+        Err(EvalError::Unbound { .. })
+    ));
+}
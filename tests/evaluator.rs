@@ -0,0 +1,68 @@
+use owl::{
+    evaluator::{eval, Arity, EvalError, EvalResult, Evaluator, Intrinsic},
+    values::{
+        Env,
+        Value::{self, Bool, Num},
+    },
+};
+
+#[test]
+fn intrinsics_receive_evaluated_arguments() {
+    assert_eq!(eval("(- (+ 1 2) 1)"), Ok(Num(2.0)));
+    assert_eq!(eval("(/ (* 2 6) 3)"), Ok(Num(4.0)));
+    assert_eq!(eval("(= (+ 1 2) 3)"), Ok(Bool(true)));
+    assert_eq!(eval("(= 3 (+ 1 1))"), Ok(Bool(false)));
+    assert_eq!(eval("(- 5)"), Ok(Num(-5.0)));
+}
+
+#[test]
+fn arity_is_checked_by_the_evaluator() {
+    assert_eq!(
+        eval("(- )"),
+        Err(EvalError::Arity {
+            name: "-".into(),
+            expected: Arity::at_least(1),
+            got: 0
+        })
+    );
+    assert_eq!(
+        eval("(eval 1 2)"),
+        Err(EvalError::Arity {
+            name: "eval".into(),
+            expected: Arity::exactly(1),
+            got: 2
+        })
+    );
+}
+
+struct Forms;
+impl Intrinsic for Forms {
+    fn name(&self) -> &'static str {
+        "forms"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::between(1, 2)
+    }
+
+    fn raw(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, _evaluator: &Evaluator, _env: &Env, args: &[Value]) -> EvalResult {
+        Ok(Value::list(args.to_vec()))
+    }
+}
+
+#[test]
+fn raw_intrinsics_receive_unevaluated_forms() {
+    let mut evaluator = Evaluator::new();
+    evaluator.add_intrinsic(Forms);
+    let env = Env::new();
+    let forms = evaluator.eval(&env, "(forms (+ 1 2))").unwrap();
+    assert_eq!(forms.to_string(), "((+ 1 2))");
+    assert!(matches!(
+        evaluator.eval(&env, "(forms 1 2 3)"),
+        Err(EvalError::Arity { got: 3, .. })
+    ));
+}
@@ -0,0 +1,178 @@
+use std::{
+    cell::Cell,
+    rc::Rc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use owl::{
+    evaluator::{EvalError, Evaluator},
+    values::{
+        Env, OwlError,
+        Value::{self, Num, Str},
+    },
+};
+
+fn run(code: &str) -> Result<Value, EvalError> {
+    Evaluator::new().eval(&Env::new(), code)
+}
+
+fn show(code: &str) -> String {
+    run(code).unwrap().to_string()
+}
+
+#[test]
+fn thrown_errors_are_caught_with_their_parts() {
+    let code = r#"
+        (try
+          (throw (error :parse "bad input" (list 1 2)))
+          (catch e (list (error-kind e) (error-message e) (error-payload e) (error? e))))
+    "#;
+    assert_eq!(show(code), "(:parse bad input (1 2) #t)");
+    assert_eq!(show("(try (+ 1 2) (catch e 0))"), "3");
+}
+
+#[test]
+fn evaluator_errors_become_error_values() {
+    assert_eq!(
+        show(r#"(try (sqrt "a") (catch e (list (error-kind e) (error-payload e))))"#),
+        "(:type sqrt)"
+    );
+    assert_eq!(
+        show("(try (missing 1) (catch e (list (error-kind e) (error-message e))))"),
+        "(:unbound missing is not defined)"
+    );
+    assert_eq!(show("(try (car) (catch e (error-kind e)))"), ":arity");
+}
+
+#[test]
+fn other_thrown_values_are_wrapped() {
+    assert_eq!(
+        show(r#"(try (throw "oops") (catch e (list (error-kind e) (error-payload e))))"#),
+        "(:thrown oops)"
+    );
+    assert_eq!(run("(throw 42)"), Err(EvalError::Thrown(Num(42.0))));
+    assert_eq!(
+        run("(throw 42)").unwrap_err().to_string(),
+        "uncaught exception: 42"
+    );
+}
+
+#[test]
+fn errors_can_be_rethrown_and_compared() {
+    let code = r#"
+        (def original (error :io "disk full"))
+        (def caught
+          (try
+            (try (throw original) (catch e (throw e)))
+            (catch e e)))
+        (list (= caught original) (= caught (error :io "disk full")) (= caught (error :io "other")))
+    "#;
+    assert_eq!(show(code), "(#t #t #f)");
+    let expected = Value::Error(Rc::new(OwlError::new("io", "disk full", Value::None)));
+    assert_eq!(
+        run(r#"(throw (error :io "disk full"))"#),
+        Err(EvalError::Thrown(expected))
+    );
+    assert_eq!(
+        run(r#"(throw (error :io "disk full"))"#)
+            .unwrap_err()
+            .to_string(),
+        "io: disk full"
+    );
+}
+
+#[test]
+fn finally_runs_on_every_exit_path() {
+    let code = r#"
+        (def log (list))
+        (fun note (x) (set log (cons x log)))
+        (try (note 1) (finally (note :ok)))
+        (try (try (throw 1) (finally (note :thrown))) (catch e none))
+        (try (throw 1) (catch e (note :caught)) (finally (note :after-catch)))
+        (try (try (throw 1) (catch e (throw 2)) (finally (note :rethrown))) (catch e none))
+        (reverse log)
+    "#;
+    assert_eq!(show(code), "(1 :ok :thrown :caught :after-catch :rethrown)");
+    assert_eq!(show("(try 1 (finally 2))"), "1");
+    assert_eq!(
+        run("(try 1 (finally (throw 2)))"),
+        Err(EvalError::Thrown(Num(2.0)))
+    );
+}
+
+#[test]
+fn host_limits_cannot_be_caught() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.set_fuel(Some(200));
+    let result = evaluator.eval(&env, "(fun spin () (spin)) (try (spin) (catch e :caught))");
+    assert_eq!(result, Err(EvalError::OutOfFuel));
+
+    evaluator.set_fuel(None);
+    evaluator.set_max_depth(Some(10));
+    let result = evaluator.eval(&env, "(try (spin) (catch e :caught))");
+    assert_eq!(result, Err(EvalError::DepthExceeded(10)));
+}
+
+#[test]
+fn host_limits_skip_finally_clauses() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.set_max_depth(Some(8));
+    let code = r#"
+        (fun f () (try (reduce (fn (a b) (+ a b)) (range 300000)) (finally (f))))
+        (f)
+    "#;
+    let handle = evaluator.interrupt_handle();
+    let timer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        handle.interrupt();
+    });
+    let started = Instant::now();
+    let result = evaluator.eval(&env, code);
+    timer.join().unwrap();
+    assert_eq!(result, Err(EvalError::Interrupted));
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    evaluator.set_max_depth(None);
+    evaluator.set_fuel(Some(500));
+    let result = evaluator.eval(&env, "(fun spin () (spin)) (try (spin) (finally (spin)))");
+    assert_eq!(result, Err(EvalError::OutOfFuel));
+}
+
+#[test]
+fn malformed_try_forms_are_syntax_errors() {
+    assert!(matches!(
+        run("(try (catch e 1) 2)"),
+        Err(EvalError::Syntax(_))
+    ));
+    assert!(matches!(
+        run("(try 1 (catch 2 3))"),
+        Err(EvalError::Syntax(_))
+    ));
+    assert!(matches!(
+        run("(try 1 (finally 2) (catch e 3))"),
+        Err(EvalError::Syntax(_))
+    ));
+}
+
+#[test]
+fn hosts_see_uncaught_errors_as_values() {
+    let error = run("(sqrt :a)").unwrap_err();
+    assert_eq!(error.kind(), "type");
+    let Value::Error(value) = error.to_value() else {
+        panic!("expected an error value");
+    };
+    assert_eq!(value.message(), error.to_string());
+    assert_eq!(value.payload(), &Str("sqrt".into()));
+
+    let calls = Rc::new(Cell::new(0));
+    let counter = calls.clone();
+    let mut evaluator = Evaluator::new();
+    evaluator.register_fn("tick", move || counter.set(counter.get() + 1));
+    evaluator
+        .eval(&Env::new(), "(try (tick) (finally (tick)))")
+        .unwrap();
+    assert_eq!(calls.get(), 2);
+}
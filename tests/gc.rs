@@ -0,0 +1,129 @@
+use owl::{
+    evaluator::Evaluator,
+    values::{
+        car, cons, Env,
+        Value::{self, Num},
+    },
+};
+
+#[test]
+fn collecting_a_dropped_global_scope() {
+    let evaluator = Evaluator::new();
+    evaluator.collect_garbage();
+    let before = evaluator.memory_stats().frames;
+
+    let env = Env::new();
+    evaluator
+        .eval(&env, "(fun f (x) x) (fun g (x) (f x))")
+        .unwrap();
+    drop(env);
+    assert_eq!(evaluator.memory_stats().frames, before + 1);
+
+    assert_eq!(evaluator.collect_garbage(), 1);
+    let stats = evaluator.memory_stats();
+    assert_eq!(stats.frames, before);
+    assert_eq!(stats.last_collected, 1);
+}
+
+#[test]
+fn collecting_call_frames_kept_alive_by_closures() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(
+            &env,
+            r#"
+            (fun make-counter ()
+              (def n 0)
+              (fun inc () (set n (+ n 1)))
+              inc)
+            (def counter (make-counter))
+            (counter)
+            "#,
+        )
+        .unwrap();
+    evaluator.collect_garbage();
+    let before = evaluator.memory_stats().frames;
+
+    for _ in 0..100 {
+        evaluator.eval(&env, "(make-counter)").unwrap();
+    }
+    assert_eq!(evaluator.memory_stats().frames, before + 100);
+    assert_eq!(evaluator.collect_garbage(), 100);
+    assert_eq!(evaluator.memory_stats().frames, before);
+
+    // The counter still referenced from the global scope survives.
+    assert_eq!(evaluator.eval(&env, "(counter)"), Ok(Num(2.0)));
+}
+
+#[test]
+fn values_held_by_the_host_are_roots() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    let counter = evaluator
+        .eval(
+            &env,
+            "(fun make () (def n 10) (fun next () (set n (+ n 1))) next) (make)",
+        )
+        .unwrap();
+    drop(env);
+
+    // The counter's frame and, through its parent, the dropped global
+    // scope are still reachable from the host.
+    assert_eq!(evaluator.collect_garbage(), 0);
+    assert_eq!(evaluator.apply(&counter, &[]), Ok(Num(11.0)));
+    drop(counter);
+    assert_eq!(evaluator.collect_garbage(), 2);
+}
+
+#[test]
+fn evaluation_collects_automatically() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(&env, "(fun make () (fun inc () 1) inc)")
+        .unwrap();
+    for _ in 0..20_000 {
+        evaluator.eval(&env, "(make)").unwrap();
+    }
+    let stats = evaluator.memory_stats();
+    assert!(stats.collections > 0);
+    assert!(stats.frames < 10_000, "{:?}", stats);
+}
+
+#[test]
+fn host_calls_collect_automatically() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(&env, "(fun make () (fun inc () 1) inc)")
+        .unwrap();
+    evaluator.collect_garbage();
+    let before = evaluator.memory_stats().collections;
+    for _ in 0..20_000 {
+        evaluator.call::<Value, _>(&env, "make", ()).unwrap();
+    }
+    let stats = evaluator.memory_stats();
+    assert!(stats.collections > before);
+    assert!(stats.frames < 10_000, "{:?}", stats);
+}
+
+#[test]
+fn lists_held_by_the_host_keep_their_closures_alive() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(&env, "(fun make () (def n 1) (fun get () n) get)")
+        .unwrap();
+
+    // A list containing a closure, shared between the global scope and the
+    // host, must survive the global scope being dropped.
+    let list = cons(evaluator.eval(&env, "(make)").unwrap(), Value::list(vec![]));
+    env.set("held", list.clone());
+    drop(env);
+    assert_eq!(evaluator.collect_garbage(), 0);
+    assert_eq!(evaluator.apply(car(&list), &[]), Ok(Num(1.0)));
+
+    drop(list);
+    assert_eq!(evaluator.collect_garbage(), 2);
+}
@@ -0,0 +1,115 @@
+use owl::{
+    evaluator::{convert_arg, Arity, EvalError, Evaluator},
+    values::{
+        Env, Handle, IntoValue, NativeType,
+        Value::{self, Atom, Bool, Num, Str},
+    },
+};
+
+struct Entity {
+    x: f64,
+    y: f64,
+}
+
+fn entity_type() -> NativeType<Entity> {
+    NativeType::new("entity")
+        .method(
+            "move",
+            Arity::exactly(2),
+            |e: &mut Entity, args: &[Value]| {
+                e.x += convert_arg::<f64>("move", 0, &args[0])?;
+                e.y += convert_arg::<f64>("move", 1, &args[1])?;
+                Ok(Value::None)
+            },
+        )
+        .method("x", Arity::exactly(0), |e: &mut Entity, _: &[Value]| {
+            Ok(e.x.into_value())
+        })
+        .printer(|e| format!("#<entity {} {}>", e.x, e.y))
+}
+
+#[test]
+fn calling_methods_on_handles() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    let entity = entity_type().wrap(Entity { x: 0.0, y: 0.0 });
+    env.set("player", entity.clone());
+
+    evaluator
+        .eval(&env, "(call player :move 1 2) (call player :move 3 4)")
+        .unwrap();
+    assert_eq!(evaluator.eval(&env, r#"(call player "x")"#), Ok(Num(4.0)));
+    assert_eq!(entity.to_string(), "#<entity 4 6>");
+
+    let handle = match entity {
+        Value::Native(handle) => handle,
+        v => panic!("Expected handle but got {:?}", v),
+    };
+    assert_eq!(handle.type_name(), "entity");
+    assert_eq!(handle.borrow::<Entity>().unwrap().y, 6.0);
+    assert!(handle.borrow::<String>().is_none());
+    assert!(!handle.is::<String>());
+}
+
+#[test]
+fn handle_errors() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    env.set("player", entity_type().wrap(Entity { x: 0.0, y: 0.0 }));
+
+    assert_eq!(
+        evaluator.eval(&env, "(call player :jump)"),
+        Err(EvalError::NoMethod {
+            type_name: "entity".into(),
+            method: "jump".into()
+        })
+    );
+    assert_eq!(
+        evaluator.eval(&env, "(call player :move 1)"),
+        Err(EvalError::Arity {
+            name: "move".into(),
+            expected: Arity::exactly(2),
+            got: 1
+        })
+    );
+    assert!(matches!(
+        evaluator.eval(&env, r#"(call player :move "left" 0)"#),
+        Err(EvalError::Type { position: 1, .. })
+    ));
+    assert!(matches!(
+        evaluator.eval(&env, "(call 1 :move)"),
+        Err(EvalError::Type { position: 1, .. })
+    ));
+}
+
+#[test]
+fn handles_compare_by_identity() {
+    let ty = entity_type();
+    let a = ty.wrap(Entity { x: 0.0, y: 0.0 });
+    let b = ty.wrap(Entity { x: 0.0, y: 0.0 });
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    env.set("a", a.clone());
+    env.set("b", b);
+    assert_eq!(evaluator.eval(&env, "(= a a)"), Ok(Bool(true)));
+    assert_eq!(evaluator.eval(&env, "(= a b)"), Ok(Bool(false)));
+
+    let plain = NativeType::<String>::new("file").wrap("data.txt".to_string());
+    assert_eq!(plain.to_string(), "#<file>");
+    assert_eq!(plain.type_name(), "file");
+}
+
+#[test]
+fn intrinsics_can_downcast_handles() {
+    let mut evaluator = Evaluator::new();
+    evaluator.register_fn("entity-y", |h: Handle| h.borrow::<Entity>().map(|e| e.y));
+    let env = Env::new();
+    env.set("e", entity_type().wrap(Entity { x: 1.0, y: 7.0 }));
+    env.set("s", Str("not an entity".into()));
+    assert_eq!(evaluator.eval(&env, "(entity-y e)"), Ok(Num(7.0)));
+    assert_eq!(evaluator.eval(&env, ":move"), Ok(Atom("move".into())));
+    assert!(matches!(
+        evaluator.eval(&env, "(entity-y s)"),
+        Err(EvalError::Type { .. })
+    ));
+}
@@ -0,0 +1,202 @@
+use std::{
+    cell::RefCell,
+    env, fs,
+    io::{self, Cursor, Write},
+    path::PathBuf,
+    process,
+    rc::Rc,
+};
+
+use owl::{
+    evaluator::{EvalError, Evaluator},
+    values::{
+        Env,
+        Value::{self, Bool, Str},
+    },
+};
+
+/// A path in the temporary directory that is unique to this test.
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("owl-{}-{}", process::id(), name))
+}
+
+fn run(code: &str) -> Result<Value, EvalError> {
+    Evaluator::new().eval(&Env::new(), code)
+}
+
+#[test]
+fn writing_appending_and_reading_files() {
+    let path = temp_path("notes.txt");
+    let path = path.to_str().unwrap();
+    let code = format!(
+        r#"
+        (write-file "{0}" "first")
+        (append-file "{0}" " second")
+        (read-file "{0}")
+        "#,
+        path
+    );
+    assert_eq!(run(&code), Ok(Str("first second".into())));
+    assert_eq!(
+        run(&format!(
+            r#"(write-file "{0}" "over") (read-file "{0}")"#,
+            path
+        )),
+        Ok(Str("over".into()))
+    );
+
+    assert_eq!(
+        run(&format!(r#"(file-exists? "{}")"#, path)),
+        Ok(Bool(true))
+    );
+    assert_eq!(
+        run(&format!(
+            r#"(delete-file "{0}") (file-exists? "{0}")"#,
+            path
+        )),
+        Ok(Bool(false))
+    );
+}
+
+#[test]
+fn iterating_over_lines() {
+    let path = temp_path("lines.txt");
+    fs::write(&path, "alpha\nbeta\r\ngamma\n").unwrap();
+    let path = path.to_str().unwrap();
+
+    assert_eq!(
+        run(&format!(r#"(read-lines "{}")"#, path)),
+        Ok(Value::list(vec![
+            Str("alpha".into()),
+            Str("beta".into()),
+            Str("gamma".into())
+        ]))
+    );
+    let code = format!(
+        r#"
+        (def seen '())
+        (each-line "{}" (fn (line) (set seen (cons (string-length line) seen))))
+        seen
+        "#,
+        path
+    );
+    assert_eq!(run(&code), run("'(5 4 5)"));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn failures_are_errors() {
+    let path = temp_path("missing.txt");
+    let path = path.to_str().unwrap();
+    for name in ["read-file", "read-lines", "delete-file"] {
+        match run(&format!(r#"({} "{}")"#, name, path)) {
+            Err(EvalError::Io(msg)) => {
+                assert!(msg.starts_with(name), "{}", msg);
+                assert!(msg.contains(path), "{}", msg);
+            }
+            other => panic!("{} returned {:?}", name, other),
+        }
+    }
+    assert!(matches!(
+        run(&format!(r#"(each-line "{}" (fn (l) l))"#, path)),
+        Err(EvalError::Io(_))
+    ));
+    assert!(matches!(
+        run(r#"(write-file "/" "x")"#),
+        Err(EvalError::Io(_))
+    ));
+    assert!(matches!(
+        run("(read-file 1)"),
+        Err(EvalError::Type { position: 1, .. })
+    ));
+}
+
+#[test]
+fn capturing_printed_output() {
+    let mut evaluator = Evaluator::new();
+    let output = evaluator.capture_output();
+    let errors = evaluator.capture_error_output();
+    let env = Env::new();
+
+    assert_eq!(
+        evaluator.eval(
+            &env,
+            r#"(print "a" 1) (println " b" :c '(1 2)) (eprintln "oops")"#
+        ),
+        Ok(Value::None)
+    );
+    assert_eq!(output.contents(), "a 1 b :c (1 2)\n");
+    assert_eq!(errors.take(), "oops\n");
+    assert_eq!(errors.contents(), "");
+
+    evaluator.eval(&env, r#"(eprint "ü")"#).unwrap();
+    assert_eq!(errors.contents(), "ü");
+}
+
+#[test]
+fn reading_lines_from_the_input() {
+    let mut evaluator = Evaluator::new();
+    evaluator.set_input(Cursor::new("first\r\nsecond\n\nlast"));
+    let env = Env::new();
+
+    let lines = evaluator.eval(
+        &env,
+        "(list (read-line) (read-line) (read-line) (read-line) (read-line))",
+    );
+    assert_eq!(
+        lines,
+        Ok(Value::list(vec![
+            Str("first".into()),
+            Str("second".into()),
+            Str("".into()),
+            Str("last".into()),
+            Value::None,
+        ]))
+    );
+}
+
+#[test]
+fn output_goes_to_any_writer() {
+    struct Lines(Rc<RefCell<Vec<String>>>);
+    impl Write for Lines {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0
+                .borrow_mut()
+                .push(String::from_utf8_lossy(buf).trim_end().to_string());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut evaluator = Evaluator::new();
+    evaluator.set_output(Lines(log.clone()));
+    evaluator
+        .eval(&Env::new(), r#"(println "one") (println "two")"#)
+        .unwrap();
+    assert_eq!(*log.borrow(), vec!["one", "two"]);
+}
+
+#[test]
+fn write_failures_are_errors() {
+    struct Broken;
+    impl Write for Broken {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("console closed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut evaluator = Evaluator::new();
+    evaluator.set_output(Broken);
+    assert_eq!(
+        evaluator.eval(&Env::new(), r#"(println "x")"#),
+        Err(EvalError::Io("println: console closed".into()))
+    );
+}
@@ -0,0 +1,262 @@
+use std::{env, fs, process, thread, time::Duration};
+
+use owl::{
+    evaluator::{EvalError, Evaluator, DEFAULT_MAX_DEPTH, DEFAULT_MEMORY_LIMIT},
+    values::{gc, Env, Value::Num},
+};
+
+const LIMIT: usize = 1 << 20;
+
+#[test]
+fn running_out_of_fuel() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(&env, "(fun spin (n) (map (fn (x) (+ x n)) (range 100000)))")
+        .unwrap();
+
+    evaluator.set_fuel(Some(1000));
+    assert_eq!(evaluator.eval(&env, "(spin 1)"), Err(EvalError::OutOfFuel));
+    assert_eq!(evaluator.fuel(), Some(0));
+    assert_eq!(evaluator.eval(&env, "1"), Err(EvalError::OutOfFuel));
+
+    // Refuelling lets the same evaluator carry on.
+    evaluator.set_fuel(Some(1000));
+    assert_eq!(evaluator.eval(&env, "(+ 1 2)"), Ok(Num(3.0)));
+    let left = evaluator.fuel().unwrap();
+    assert!(left < 1000 && left > 990, "{}", left);
+
+    evaluator.set_fuel(None);
+    assert!(evaluator.eval(&env, "(length (spin 1))").is_ok());
+    assert_eq!(evaluator.fuel(), None);
+}
+
+#[test]
+fn limiting_call_depth() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(
+            &env,
+            r#"
+            (fun forever (n) (forever (+ n 1)))
+            (fun count (n) (if (= n 0) 0 (+ 1 (count (- n 1)))))
+            "#,
+        )
+        .unwrap();
+    evaluator.set_max_depth(Some(100));
+
+    assert_eq!(
+        evaluator.eval(&env, "(forever 0)"),
+        Err(EvalError::DepthExceeded(100))
+    );
+    assert_eq!(evaluator.eval(&env, "(count 20)"), Ok(Num(20.0)));
+    assert_eq!(
+        evaluator.eval(&env, "(count 30)"),
+        Err(EvalError::DepthExceeded(100))
+    );
+    // Calls made by intrinsics count too.
+    assert_eq!(
+        evaluator.eval(&env, "(map forever '(1))"),
+        Err(EvalError::DepthExceeded(100))
+    );
+    // So do nested forms without any calls.
+    let nested = "(+ 1 ".repeat(200) + "0" + &")".repeat(200);
+    assert_eq!(
+        evaluator.eval(&env, &nested),
+        Err(EvalError::DepthExceeded(100))
+    );
+    assert_eq!(
+        evaluator.eval(
+            &env,
+            "(eval (fold (fn (acc x) (list '+ acc 1)) 0 (range 100000)))"
+        ),
+        Err(EvalError::DepthExceeded(100))
+    );
+
+    evaluator.set_max_depth(None);
+    assert_eq!(evaluator.eval(&env, "(count 40)"), Ok(Num(40.0)));
+    assert_eq!(evaluator.eval(&env, &nested), Ok(Num(200.0)));
+}
+
+#[test]
+fn runaway_recursion_fails_under_the_default_depth_limit() {
+    // The default is sized for the 8 MB stack of a main thread.
+    let child = thread::Builder::new()
+        .stack_size(8 << 20)
+        .spawn(|| {
+            let evaluator = Evaluator::sandboxed();
+            let env = Env::new();
+            let result = evaluator.eval(&env, "(fun f (n) (+ 1 (f n))) (f 0)");
+            result == Err(EvalError::DepthExceeded(DEFAULT_MAX_DEPTH))
+        })
+        .unwrap();
+    assert!(child.join().unwrap());
+}
+
+#[test]
+fn interrupting_from_another_thread() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    let handle = evaluator.interrupt_handle();
+    let timer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        handle.interrupt();
+    });
+
+    let result = evaluator.eval(
+        &env,
+        "(reduce (fn (acc x) (map (fn (y) y) (range 1000))) 0 (range 100000))",
+    );
+    timer.join().unwrap();
+    assert_eq!(result, Err(EvalError::Interrupted));
+    assert_eq!(evaluator.eval(&env, "(+ 1 2)"), Ok(Num(3.0)));
+}
+
+#[test]
+fn pending_interrupts_stop_the_next_evaluation() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.interrupt_handle().interrupt();
+    assert_eq!(evaluator.eval(&env, "(+ 1 2)"), Err(EvalError::Interrupted));
+    assert_eq!(evaluator.eval(&env, "(+ 1 2)"), Ok(Num(3.0)));
+    assert_eq!(
+        EvalError::Interrupted.to_string(),
+        "evaluation was interrupted"
+    );
+}
+
+#[test]
+fn huge_ranges_are_refused_before_allocating() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.set_memory_limit(Some(LIMIT));
+    assert_eq!(
+        evaluator.eval(&env, "(length (range 1e12))"),
+        Err(EvalError::OutOfMemory(LIMIT))
+    );
+    assert_eq!(
+        evaluator.eval(&env, "(append (range 10000) (range 10000))"),
+        Err(EvalError::OutOfMemory(LIMIT))
+    );
+    assert_eq!(evaluator.eval(&env, "(length (range 100))"), Ok(Num(100.0)));
+}
+
+#[test]
+fn growing_strings_hit_the_limit() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(&env, r#"(fun grow (s) (grow (string-append s s)))"#)
+        .unwrap();
+    evaluator.set_memory_limit(Some(LIMIT));
+    assert_eq!(
+        evaluator.eval(&env, r#"(grow "owl")"#),
+        Err(EvalError::OutOfMemory(LIMIT))
+    );
+
+    evaluator.set_memory_limit(Some(LIMIT));
+    assert_eq!(
+        evaluator.eval(
+            &env,
+            r#"
+            (def many (join (map (fn (x) "a") (range 1000)) ""))
+            (def big (join (map (fn (x) "bbbbbbbbbb") (range 1000)) ""))
+            (replace many "a" big)
+            "#
+        ),
+        Err(EvalError::OutOfMemory(LIMIT))
+    );
+}
+
+#[test]
+fn lists_and_environments_are_counted() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.eval(&env, "(fun id (x) x)").unwrap();
+    evaluator.set_memory_limit(Some(LIMIT));
+
+    let before = evaluator.memory_used();
+    evaluator
+        .eval(&env, "(def xs (map id (range 100)))")
+        .unwrap();
+    let used = evaluator.memory_used() - before;
+    assert!(used >= 100 * gc::CELL_BYTES, "{} bytes", used);
+    assert_eq!(
+        evaluator.eval(
+            &env,
+            "(reduce (fn (acc x) (cons x (cons x acc))) '() (range 5000))"
+        ),
+        Err(EvalError::OutOfMemory(LIMIT))
+    );
+
+    // Freed values give their memory back.
+    evaluator.eval(&env, "(set xs none)").unwrap();
+    assert!(evaluator.memory_used() <= before + 1024);
+    assert_eq!(evaluator.eval(&env, "(id 1)"), Ok(Num(1.0)));
+    evaluator.set_memory_limit(None);
+    assert!(evaluator
+        .eval(&env, "(reduce (fn (acc x) (cons x acc)) '() (range 50000))")
+        .is_ok());
+}
+
+#[test]
+fn only_live_memory_counts_towards_the_limit() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.set_memory_limit(Some(LIMIT));
+    evaluator.eval(&env, "(def n 0)").unwrap();
+    let before = evaluator.memory_used();
+    for _ in 0..50 {
+        assert_eq!(
+            evaluator.eval(&env, "(do (map (fn (x) (list x x x)) (range 1000)) 0)"),
+            Ok(Num(0.0))
+        );
+        evaluator.eval(&env, "(set n (+ n 1)) (def n n)").unwrap();
+    }
+    assert_eq!(evaluator.memory_used(), before);
+}
+
+#[test]
+fn evaluators_count_their_own_memory() {
+    let big = Evaluator::new();
+    let small = Evaluator::new();
+    let env = Env::new();
+    small.set_memory_limit(Some(LIMIT));
+    big.eval(&env, "(def xs (range 100000))").unwrap();
+    assert!(big.memory_used() > LIMIT);
+    assert!(small.memory_used() < 1024);
+    assert_eq!(
+        small.eval(&Env::new(), "(length (range 100))"),
+        Ok(Num(100.0))
+    );
+}
+
+#[test]
+fn huge_ranges_fail_under_the_default_limit() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    assert_eq!(
+        evaluator.eval(&env, "(range 1e12)"),
+        Err(EvalError::OutOfMemory(DEFAULT_MEMORY_LIMIT))
+    );
+    assert!(matches!(
+        evaluator.eval(&env, "(range 0 (/ 1 0))"),
+        Err(EvalError::Native(_))
+    ));
+}
+
+#[test]
+fn reading_large_files_is_refused() {
+    let path = env::temp_dir().join(format!("owl-{}-large.txt", process::id()));
+    fs::write(&path, "x".repeat(4096)).unwrap();
+    let evaluator = Evaluator::new();
+    evaluator.set_memory_limit(Some(1024));
+    for name in ["read-file", "read-lines"] {
+        assert_eq!(
+            evaluator.eval(&Env::new(), format!(r#"({} "{}")"#, name, path.display())),
+            Err(EvalError::OutOfMemory(1024))
+        );
+    }
+    fs::remove_file(path).unwrap();
+}
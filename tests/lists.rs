@@ -0,0 +1,168 @@
+use owl::{
+    evaluator::{eval, EvalError, Evaluator},
+    values::{
+        Env,
+        Value::{self, Bool, Num},
+    },
+};
+
+fn nums(xs: &[f64]) -> Value {
+    Value::list(xs.iter().map(|x| Num(*x)).collect())
+}
+
+#[test]
+fn building_and_taking_apart_lists() {
+    assert_eq!(eval("(list 1 2 3)"), Ok(nums(&[1.0, 2.0, 3.0])));
+    assert_eq!(eval("(list)"), Ok(nums(&[])));
+    assert_eq!(eval("(cons 1 (list 2 3))"), Ok(nums(&[1.0, 2.0, 3.0])));
+    assert_eq!(eval("(cons 1 2)"), eval("'(1 . 2)"));
+    assert_eq!(eval("(car '(1 2))"), Ok(Num(1.0)));
+    assert_eq!(eval("(cdr '(1 2))"), Ok(nums(&[2.0])));
+    assert_eq!(eval("(cdr '(1 . 2))"), Ok(Num(2.0)));
+    assert_eq!(eval("(car '())"), Ok(Value::None));
+    assert_eq!(eval("(first '(4 5))"), Ok(Num(4.0)));
+    assert_eq!(eval("(rest '(4 5))"), Ok(nums(&[5.0])));
+    assert_eq!(eval("(rest '())"), Ok(nums(&[])));
+    assert_eq!(
+        eval("(car 1)"),
+        Err(EvalError::Type {
+            name: "car".into(),
+            position: 1,
+            expected: "list".into(),
+            got: "number".into(),
+        })
+    );
+}
+
+#[test]
+fn indexing_and_measuring() {
+    assert_eq!(eval("(nth '(1 2 3) 2)"), Ok(Num(3.0)));
+    assert_eq!(eval("(nth '(1 2 3) 3)"), Ok(Value::None));
+    assert_eq!(eval("(nth '(1 2 3) -1)"), Ok(Value::None));
+    assert_eq!(eval("(length '(1 2 3))"), Ok(Num(3.0)));
+    assert_eq!(eval("(length '())"), Ok(Num(0.0)));
+}
+
+#[test]
+fn appending_and_reversing() {
+    assert_eq!(
+        eval("(append '(1) '() '(2 3) '(4))"),
+        Ok(nums(&[1.0, 2.0, 3.0, 4.0]))
+    );
+    assert_eq!(eval("(append)"), Ok(nums(&[])));
+    assert_eq!(eval("(reverse '(1 2 3))"), Ok(nums(&[3.0, 2.0, 1.0])));
+}
+
+#[test]
+fn ranges() {
+    assert_eq!(eval("(range 3)"), Ok(nums(&[0.0, 1.0, 2.0])));
+    assert_eq!(eval("(range 2 5)"), Ok(nums(&[2.0, 3.0, 4.0])));
+    assert_eq!(eval("(range 0 10 4)"), Ok(nums(&[0.0, 4.0, 8.0])));
+    assert_eq!(eval("(range 3 0 -1)"), Ok(nums(&[3.0, 2.0, 1.0])));
+    assert_eq!(eval("(range 5 2)"), Ok(nums(&[])));
+    assert!(matches!(eval("(range 0 1 0)"), Err(EvalError::Native(_))));
+    assert!(matches!(
+        eval("(range 0 (/ 1 0))"),
+        Err(EvalError::Native(_))
+    ));
+    assert!(matches!(eval("(range (/ 0 0))"), Err(EvalError::Native(_))));
+    assert!(matches!(
+        eval("(range 0 1 (/ -1 0))"),
+        Err(EvalError::Native(_))
+    ));
+    assert!(matches!(eval("(range 1e300)"), Err(EvalError::Native(_))));
+}
+
+#[test]
+fn mapping_and_filtering() {
+    assert_eq!(
+        eval("(map (fn (x) (* x x)) '(1 2 3))"),
+        Ok(nums(&[1.0, 4.0, 9.0]))
+    );
+    assert_eq!(eval("(map + '(1 2 3) '(10 20))"), Ok(nums(&[11.0, 22.0])));
+    assert_eq!(
+        eval("(filter (fn (x) (= x 2)) '(1 2 3 2))"),
+        Ok(nums(&[2.0, 2.0]))
+    );
+    assert_eq!(
+        eval("(map 1 '(1))"),
+        Err(EvalError::NotCallable("number".into()))
+    );
+}
+
+#[test]
+fn reducing_and_folding() {
+    assert_eq!(eval("(reduce + '(1 2 3 4))"), Ok(Num(10.0)));
+    assert_eq!(eval("(reduce + 10 '(1 2))"), Ok(Num(13.0)));
+    assert_eq!(eval("(reduce + 10 '())"), Ok(Num(10.0)));
+    assert!(matches!(eval("(reduce + '())"), Err(EvalError::Native(_))));
+    assert_eq!(
+        eval("(fold (fn (acc x) (cons x acc)) '() '(1 2 3))"),
+        Ok(nums(&[3.0, 2.0, 1.0]))
+    );
+}
+
+#[test]
+fn searching() {
+    assert_eq!(eval("(any? (fn (x) (= x 2)) '(1 2 3))"), Ok(Bool(true)));
+    assert_eq!(eval("(any? (fn (x) (= x 5)) '(1 2 3))"), Ok(Bool(false)));
+    assert_eq!(eval("(all? (fn (x) x) '(1 2 3))"), Ok(Bool(true)));
+    assert_eq!(eval("(all? (fn (x) x) '(1 #f 3))"), Ok(Bool(false)));
+    assert_eq!(eval("(all? (fn (x) x) '())"), Ok(Bool(true)));
+    assert_eq!(eval("(find (fn (x) (= x 3)) '(1 2 3 4))"), Ok(Num(3.0)));
+    assert_eq!(eval("(find (fn (x) #f) '(1 2))"), Ok(Value::None));
+}
+
+#[test]
+fn zipping_taking_and_dropping() {
+    assert_eq!(
+        eval("(zip '(1 2 3) '(4 5))"),
+        Ok(Value::list(vec![nums(&[1.0, 4.0]), nums(&[2.0, 5.0])]))
+    );
+    assert_eq!(eval("(take 2 '(1 2 3))"), Ok(nums(&[1.0, 2.0])));
+    assert_eq!(eval("(take 5 '(1 2))"), Ok(nums(&[1.0, 2.0])));
+    assert_eq!(eval("(drop 2 '(1 2 3))"), Ok(nums(&[3.0])));
+    assert_eq!(eval("(drop 5 '(1 2 3))"), Ok(nums(&[])));
+    assert_eq!(eval("(drop 1e12 '(1 2 3))"), Ok(nums(&[])));
+}
+
+#[test]
+fn sorting() {
+    assert_eq!(eval("(sort '(3 1 2))"), Ok(nums(&[1.0, 2.0, 3.0])));
+    assert_eq!(eval(r#"(sort '("b" "c" "a"))"#), eval(r#"'("a" "b" "c")"#));
+    assert!(matches!(
+        eval(r#"(sort '(1 "a"))"#),
+        Err(EvalError::Native(_))
+    ));
+
+    let mut evaluator = Evaluator::new();
+    evaluator.register_fn("greater?", |a: f64, b: f64| a > b);
+    let env = Env::new();
+    assert_eq!(
+        evaluator.eval(&env, "(sort '(3 1 4 1 5) greater?)"),
+        Ok(nums(&[5.0, 4.0, 3.0, 1.0, 1.0]))
+    );
+    // Equal keys keep their order.
+    assert_eq!(
+        evaluator.eval(
+            &env,
+            "(sort '((1 :a) (0 :b) (1 :c)) (fn (x y) (greater? (car x) (car y))))"
+        ),
+        evaluator.eval(&env, "'((1 :a) (1 :c) (0 :b))")
+    );
+    assert!(matches!(
+        evaluator.eval(&env, "(sort '(2 1) (fn (a b) (greater? a :b)))"),
+        Err(EvalError::Type { position: 2, .. })
+    ));
+}
+
+#[test]
+fn grouping() {
+    assert_eq!(
+        eval("(group-by (fn (x) (= x 1)) '(1 2 1 3))"),
+        Ok(Value::list(vec![
+            Value::list(vec![Bool(true), nums(&[1.0, 1.0])]),
+            Value::list(vec![Bool(false), nums(&[2.0, 3.0])]),
+        ]))
+    );
+}
@@ -0,0 +1,113 @@
+use owl::{
+    evaluator::{eval, EvalError, Evaluator},
+    values::{
+        Env,
+        Value::{self, Bool, Num, Str},
+    },
+};
+
+#[test]
+fn chained_comparisons() {
+    assert_eq!(eval("(< 1 2 3)"), Ok(Bool(true)));
+    assert_eq!(eval("(< 1 3 2)"), Ok(Bool(false)));
+    assert_eq!(eval("(<= 1 1 2)"), Ok(Bool(true)));
+    assert_eq!(eval("(> 3 2 2)"), Ok(Bool(false)));
+    assert_eq!(eval("(>= 3 2 2)"), Ok(Bool(true)));
+    assert_eq!(eval("(< 1)"), Ok(Bool(true)));
+    assert_eq!(eval(r#"(< "apple" "banana" "cherry")"#), Ok(Bool(true)));
+    assert_eq!(eval(r#"(> "a" "b")"#), Ok(Bool(false)));
+}
+
+#[test]
+fn comparing_incompatible_types_is_an_error() {
+    assert_eq!(
+        eval(r#"(< 1 2 "3")"#),
+        Err(EvalError::Type {
+            name: "<".into(),
+            position: 3,
+            expected: "number".into(),
+            got: "string".into(),
+        })
+    );
+    // The error is reported even when an earlier pair already failed.
+    assert!(matches!(
+        eval(r#"(> 1 2 "3")"#),
+        Err(EvalError::Type { position: 3, .. })
+    ));
+    assert_eq!(
+        eval("(<= :a :b)"),
+        Err(EvalError::Type {
+            name: "<=".into(),
+            position: 1,
+            expected: "number or string".into(),
+            got: "atom".into(),
+        })
+    );
+}
+
+#[test]
+fn negation_follows_truthiness() {
+    assert_eq!(eval("(not #f)"), Ok(Bool(true)));
+    assert_eq!(eval("(not 0)"), Ok(Bool(false)));
+    assert_eq!(eval("(not none)"), Ok(Bool(true)));
+    assert_eq!(eval("(not 1)"), Ok(Bool(false)));
+    assert_eq!(eval(r#"(not "")"#), Ok(Bool(false)));
+}
+
+#[test]
+fn and_or_return_the_deciding_value() {
+    assert_eq!(eval("(and 1 2 3)"), Ok(Num(3.0)));
+    assert_eq!(eval("(and 1 #f 3)"), Ok(Bool(false)));
+    assert_eq!(eval("(and)"), Ok(Bool(true)));
+    assert_eq!(eval(r#"(or #f none "x" 3)"#), Ok(Str("x".into())));
+    assert_eq!(eval("(or #f none)"), Ok(Value::None));
+    assert_eq!(eval("(or)"), Ok(Bool(false)));
+}
+
+#[test]
+fn and_or_short_circuit() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.eval(&env, "(def calls 0)").unwrap();
+    evaluator
+        .eval(&env, "(fun touch (x) (set calls (+ calls 1)) x)")
+        .unwrap();
+
+    assert_eq!(
+        evaluator.eval(&env, "(and (touch #f) (touch 1))"),
+        Ok(Bool(false))
+    );
+    assert_eq!(
+        evaluator.eval(&env, "(or (touch 2) (touch 3))"),
+        Ok(Num(2.0))
+    );
+    assert_eq!(env.get("calls"), Num(2.0));
+    assert_eq!(
+        evaluator.eval(&env, "(and 1 (missing))"),
+        Err(EvalError::Unbound {
+            name: "missing".into(),
+            suggestions: vec![],
+        })
+    );
+}
+
+#[test]
+fn bounded_loops() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(
+            &env,
+            r#"
+            (fun count-up (i n acc)
+              (if (and (>= i 0) (< i n))
+                (count-up (+ i 1) n (cons i acc))
+                acc))
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        evaluator.eval(&env, "(count-up 0 3 '())"),
+        Ok(Value::list(vec![Num(2.0), Num(1.0), Num(0.0)]))
+    );
+}
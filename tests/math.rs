@@ -0,0 +1,197 @@
+use std::f64::consts::{E, PI};
+
+use owl::{
+    evaluator::{eval, EvalError, Evaluator},
+    values::{
+        Env,
+        Value::{self, Num},
+    },
+};
+
+fn num(code: &str) -> f64 {
+    match eval(code) {
+        Ok(Num(n)) => n,
+        v => panic!("{} evaluated to {:?}", code, v),
+    }
+}
+
+#[test]
+fn remainders() {
+    assert_eq!(num("(mod 7 3)"), 1.0);
+    assert_eq!(num("(mod -7 3)"), 2.0);
+    assert_eq!(num("(mod 7 -3)"), -2.0);
+    assert_eq!(num("(rem -7 3)"), -1.0);
+    assert_eq!(num("(rem 7 -3)"), 1.0);
+    assert_eq!(num("(quot 7 2)"), 3.0);
+    assert_eq!(num("(quot -7 2)"), -3.0);
+}
+
+#[test]
+fn integer_division_by_zero_fails() {
+    for code in ["(quot 1 0)", "(mod 1 0)", "(rem 1 0)"] {
+        assert!(matches!(
+            eval(code),
+            Err(EvalError::Arithmetic { message, .. }) if message == "division by zero"
+        ));
+    }
+}
+
+#[test]
+fn float_division_follows_ieee_754() {
+    assert_eq!(num("(/ 1 0)"), f64::INFINITY);
+    assert_eq!(num("(/ -1 0)"), f64::NEG_INFINITY);
+    assert!(num("(/ 0 0)").is_nan());
+    assert_eq!(num("(/ 4)"), 0.25);
+}
+
+#[test]
+fn arithmetic_rejects_other_types() {
+    assert_eq!(
+        eval(r#"(+ 1 2 "3")"#),
+        Err(EvalError::Type {
+            name: "+".into(),
+            position: 3,
+            expected: "number".into(),
+            got: "string".into(),
+        })
+    );
+    for (code, position, got) in [
+        ("(* 2 none)", 2, "none"),
+        ("(- :a)", 1, "atom"),
+        ("(/ 1 (list 2))", 2, "list"),
+    ] {
+        assert!(matches!(
+            eval(code),
+            Err(EvalError::Type { position: p, got: g, .. }) if p == position && g == got
+        ));
+    }
+}
+
+#[test]
+fn checked_arithmetic_rejects_nan_and_infinity() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.set_checked_arithmetic(true);
+    assert_eq!(
+        evaluator.eval(&env, "(/ 1 0)"),
+        Err(EvalError::Arithmetic {
+            name: "/".into(),
+            message: "result is infinite".into(),
+        })
+    );
+    assert_eq!(
+        evaluator.eval(&env, "(sqrt -1)"),
+        Err(EvalError::Arithmetic {
+            name: "sqrt".into(),
+            message: "result is not a number".into(),
+        })
+    );
+    assert_eq!(evaluator.eval(&env, "(/ 1 4)"), Ok(Num(0.25)));
+    assert_eq!(
+        evaluator
+            .eval(&env, "(try (* 1e308 10) (catch e (error-kind e)))")
+            .unwrap()
+            .to_string(),
+        ":arithmetic"
+    );
+}
+
+#[test]
+fn rounding() {
+    assert_eq!(num("(abs -2.5)"), 2.5);
+    assert_eq!(num("(floor -2.5)"), -3.0);
+    assert_eq!(num("(ceil -2.5)"), -2.0);
+    assert_eq!(num("(round 2.5)"), 3.0);
+    assert_eq!(num("(trunc -2.7)"), -2.0);
+}
+
+#[test]
+fn powers_and_logarithms() {
+    assert_eq!(num("(sqrt 16)"), 4.0);
+    assert_eq!(num("(pow 2 10)"), 1024.0);
+    assert_eq!(num("(exp 0)"), 1.0);
+    assert_eq!(num("(log e)"), 1.0);
+    assert!((num("(log 1000 10)") - 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn trigonometry_and_constants() {
+    assert_eq!(num("pi"), PI);
+    assert_eq!(num("e"), E);
+    assert_eq!(num("(sin 0)"), 0.0);
+    assert_eq!(num("(cos 0)"), 1.0);
+    assert!((num("(tan (/ pi 4))") - 1.0).abs() < 1e-12);
+    assert_eq!(num("(atan2 1 1)"), PI / 4.0);
+    // Bindings shadow constants.
+    assert_eq!(num("(def pi 3) pi"), 3.0);
+}
+
+#[test]
+fn ranges_and_interpolation() {
+    assert_eq!(num("(min 3 1 2)"), 1.0);
+    assert_eq!(num("(max 3 1 2)"), 3.0);
+    assert_eq!(num("(clamp 12 0 10)"), 10.0);
+    assert_eq!(num("(lerp 10 20 0.25)"), 12.5);
+    assert_eq!(
+        eval(r#"(max 1 "2")"#),
+        Err(EvalError::Type {
+            name: "max".into(),
+            position: 2,
+            expected: "number".into(),
+            got: "string".into(),
+        })
+    );
+}
+
+#[test]
+fn seeded_randomness_is_repeatable() {
+    let script = "(random-seed 42) (list (random) (random-int 10) (random-int -5 5) (shuffle (range 8)) (choose '(:a :b :c)))";
+    let first = eval(script).unwrap();
+    assert_eq!(eval(script), Ok(first.clone()));
+    assert_ne!(
+        eval(script.replace("42", "43")),
+        Ok(first),
+        "different seeds give different runs"
+    );
+
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.seed_random(7);
+    let a = evaluator.eval(&env, "(random)");
+    evaluator.seed_random(7);
+    assert_eq!(evaluator.eval(&env, "(random)"), a);
+}
+
+#[test]
+fn random_values_stay_in_range() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.seed_random(1);
+    for _ in 0..200 {
+        let x = evaluator.call::<f64, _>(&env, "random", ()).unwrap();
+        assert!((0.0..1.0).contains(&x));
+        let n = evaluator
+            .call::<i64, _>(&env, "random-int", (-3i64, 3i64))
+            .unwrap();
+        assert!((-3..3).contains(&n));
+    }
+    assert!(matches!(
+        evaluator.eval(&env, "(random-int 0)"),
+        Err(EvalError::Native(_))
+    ));
+}
+
+#[test]
+fn shuffling_and_choosing() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    let shuffled = evaluator
+        .call::<Vec<f64>, _>(&env, "shuffle", (vec![1.0, 2.0, 3.0, 4.0, 5.0],))
+        .unwrap();
+    let mut sorted = shuffled.clone();
+    sorted.sort_by(f64::total_cmp);
+    assert_eq!(sorted, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+
+    assert_eq!(evaluator.eval(&env, "(choose '())"), Ok(Value::None));
+    assert_eq!(evaluator.eval(&env, "(choose '(9))"), Ok(Num(9.0)));
+}
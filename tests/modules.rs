@@ -0,0 +1,188 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use owl::{
+    evaluator::{Capabilities, Capability, EvalError, Evaluator},
+    values::{
+        Env,
+        Value::{self, Num},
+    },
+};
+
+/// A fresh directory in the temporary directory that is unique to this test.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("owl-{}-{}", process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, code: &str) -> PathBuf {
+    let path = dir.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, code).unwrap();
+    path
+}
+
+fn run(code: &str) -> Result<Value, EvalError> {
+    Evaluator::new().eval(&Env::new(), code)
+}
+
+#[test]
+fn modules_export_only_listed_names() {
+    let code = r#"
+        (module geometry (export area)
+          (def scale 2)
+          (fun area (w h) (* scale w h)))
+        (import geometry)
+        (area 3 4)
+    "#;
+    assert_eq!(run(code), Ok(Value::Num(24.0)));
+    assert!(matches!(
+        run(&format!("{} scale", code)),
+        Err(EvalError::Unbound { name, .. }) if name == "scale"
+    ));
+}
+
+#[test]
+fn imports_with_an_alias_prefix_exported_names() {
+    let code = r#"
+        (module counter (export start) (def start 10))
+        (import counter :as c)
+        c/start
+    "#;
+    assert_eq!(run(code), Ok(Value::Num(10.0)));
+    assert!(matches!(
+        run(&format!("{} start", code)),
+        Err(EvalError::Unbound { name, .. }) if name == "start"
+    ));
+}
+
+#[test]
+fn modules_must_define_what_they_export() {
+    assert!(matches!(
+        run("(module m (export missing) (def x 1))"),
+        Err(EvalError::Syntax(_))
+    ));
+    assert!(matches!(
+        run("(module m (def x 1))"),
+        Err(EvalError::Syntax(_))
+    ));
+    assert!(matches!(run("(import m :to x)"), Err(EvalError::Syntax(_))));
+}
+
+#[test]
+fn files_are_resolved_relative_to_the_importing_file() {
+    let dir = temp_dir("relative");
+    write(
+        &dir,
+        "lib/util.owl",
+        "(module util (export twice) (fun twice (x) (* 2 x)))",
+    );
+    write(
+        &dir,
+        "lib/shapes.owl",
+        r#"(module shapes (export square)
+             (import "util.owl")
+             (fun square (x) (twice (twice x))))"#,
+    );
+    let main = write(
+        &dir,
+        "main.owl",
+        r#"(import "lib/shapes.owl" :as s) (s/square 3)"#,
+    );
+    let evaluator = Evaluator::new();
+    assert_eq!(evaluator.eval_file(&Env::new(), &main), Ok(Num(12.0)));
+}
+
+#[test]
+fn modules_are_found_on_the_search_path() {
+    let dir = temp_dir("search");
+    write(
+        &dir,
+        "strs.owl",
+        "(module strs (export greeting) (def greeting 1))",
+    );
+    let mut evaluator = Evaluator::new();
+    let env = Env::new();
+    assert!(matches!(
+        evaluator.eval(&env, "(import strs)"),
+        Err(EvalError::ModuleNotFound(name)) if name == "strs.owl"
+    ));
+    evaluator.add_module_path(&dir);
+    assert_eq!(evaluator.eval(&env, "(import strs) greeting"), Ok(Num(1.0)));
+}
+
+#[test]
+fn modules_are_evaluated_once() {
+    let dir = temp_dir("once");
+    let log = dir.join("log.txt");
+    write(
+        &dir,
+        "noisy.owl",
+        &format!(
+            r#"(module noisy (export x) (append-file "{}" "loaded ") (def x 1))"#,
+            log.display()
+        ),
+    );
+    let main = write(
+        &dir,
+        "main.owl",
+        r#"(import "noisy.owl") (import "./noisy.owl" :as n) (import noisy) (+ x n/x)"#,
+    );
+    let evaluator = Evaluator::new();
+    assert_eq!(evaluator.eval_file(&Env::new(), &main), Ok(Num(2.0)));
+    assert_eq!(fs::read_to_string(&log).unwrap(), "loaded ");
+}
+
+#[test]
+fn import_cycles_are_reported() {
+    let dir = temp_dir("cycle");
+    write(
+        &dir,
+        "a.owl",
+        r#"(module a (export x) (import "b.owl") (def x 1))"#,
+    );
+    write(
+        &dir,
+        "b.owl",
+        r#"(module b (export y) (import "a.owl") (def y 2))"#,
+    );
+    let main = write(&dir, "main.owl", r#"(import "a.owl")"#);
+    let result = Evaluator::new().eval_file(&Env::new(), &main);
+    let Err(EvalError::ImportCycle(files)) = result else {
+        panic!("expected an import cycle, got {:?}", result);
+    };
+    let names = files
+        .iter()
+        .map(|f| f.rsplit(['/', '\\']).next().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["a.owl", "b.owl", "a.owl"]);
+    assert_eq!(
+        EvalError::ImportCycle(vec!["a.owl".into(), "b.owl".into(), "a.owl".into()]).to_string(),
+        "import cycle: a.owl -> b.owl -> a.owl"
+    );
+}
+
+#[test]
+fn importing_files_needs_the_files_capability() {
+    let dir = temp_dir("sandbox");
+    write(&dir, "m.owl", "(module m (export x) (def x 1))");
+    let mut evaluator = Evaluator::with_capabilities(Capabilities::sandbox());
+    evaluator.add_module_path(&dir);
+    let env = Env::new();
+    assert_eq!(
+        evaluator.eval(&env, "(import m)"),
+        Err(EvalError::PermissionDenied {
+            name: "import".into(),
+            capability: Capability::Files,
+        })
+    );
+    assert_eq!(
+        evaluator.eval(&env, "(module m (export x) (def x 2)) (import m) x"),
+        Ok(Num(2.0))
+    );
+}
@@ -0,0 +1,136 @@
+use owl::{
+    evaluator::{EvalError, Evaluator},
+    values::{
+        Env,
+        Value::{self, Num},
+    },
+};
+
+fn run(code: &str) -> Result<Value, EvalError> {
+    Evaluator::new().eval(&Env::new(), code)
+}
+
+#[test]
+fn modules_defining_the_same_name_do_not_clash() {
+    let code = r#"
+        (module json (export parse) (fun parse (s) (string-append "json:" s)))
+        (module csv (export parse) (fun parse (s) (split s ",")))
+        (list (json/parse "x") (csv.parse "a,b"))
+    "#;
+    assert_eq!(run(code).unwrap().to_string(), "(json:x (a b))");
+    assert!(matches!(
+        run(&format!("{} parse", code)),
+        Err(EvalError::Unbound { name, .. }) if name == "parse"
+    ));
+}
+
+#[test]
+fn qualified_lookups_only_see_exported_names() {
+    let code = r#"
+        (module shapes (export area) (def scale 2) (fun area (x) (* scale x)))
+        (shapes/area 3)
+    "#;
+    assert_eq!(run(code), Ok(Num(6.0)));
+    assert!(matches!(
+        run(&format!("{} shapes/scale", code)),
+        Err(EvalError::Unbound { name, .. }) if name == "shapes/scale"
+    ));
+    assert_eq!(
+        run("(module m (export x) (def x 1)) (m/y)"),
+        Err(EvalError::Unbound {
+            name: "m/y".into(),
+            suggestions: vec![],
+        })
+    );
+}
+
+#[test]
+fn aliases_name_a_module_namespace() {
+    let code = r#"
+        (module geometry (export square) (fun square (x) (* x x)))
+        (import geometry :as g)
+        (list (g/square 4) (map g/square (list 1 2)))
+    "#;
+    assert_eq!(run(code).unwrap().to_string(), "(16 (1 4))");
+}
+
+#[test]
+fn intrinsics_are_qualified_by_their_capability() {
+    assert_eq!(run("(math/sqrt 16)"), Ok(Num(4.0)));
+    assert_eq!(
+        run(r#"(map strings.upcase (list "a" "b"))"#)
+            .unwrap()
+            .to_string(),
+        "(A B)"
+    );
+    assert_eq!(
+        run(r#"(str.split "a,b" ",")"#).unwrap().to_string(),
+        "(a b)"
+    );
+    assert_eq!(run("(core/in-ns user) 1"), Ok(Num(1.0)));
+    assert_eq!(run("(def file.txt 1) file.txt"), Ok(Num(1.0)));
+    assert_eq!(
+        run("(math/upcase 1)"),
+        Err(EvalError::Unbound {
+            name: "math/upcase".into(),
+            suggestions: vec![],
+        })
+    );
+}
+
+#[test]
+fn in_ns_keeps_definitions_out_of_the_global_environment() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator.eval(&env, "(def greeting 1)").unwrap();
+    evaluator
+        .eval(
+            &env,
+            r#"
+            (in-ns parser)
+            (def greeting 2)
+            (fun parse () greeting)
+            "#,
+        )
+        .unwrap();
+    assert_eq!(evaluator.namespace(), "parser");
+    // Globals stay visible inside the namespace.
+    assert_eq!(evaluator.eval(&env, "(+ greeting 0)"), Ok(Num(2.0)));
+
+    evaluator.eval(&env, "(in-ns user)").unwrap();
+    assert_eq!(evaluator.namespace(), "user");
+    assert_eq!(
+        evaluator
+            .eval(&env, "(list greeting parser/greeting (parser/parse))")
+            .unwrap()
+            .to_string(),
+        "(1 2 2)"
+    );
+    assert!(matches!(
+        evaluator.eval(&env, "parse"),
+        Err(EvalError::Unbound { .. })
+    ));
+}
+
+#[test]
+fn in_ns_namespaces_see_global_definitions() {
+    let code = r#"
+        (def base 10)
+        (in-ns counter)
+        (fun next (x) (+ base x))
+        (in-ns user)
+        (counter/next 1)
+    "#;
+    assert_eq!(run(code), Ok(Num(11.0)));
+}
+
+#[test]
+fn hosts_can_call_qualified_functions() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    evaluator
+        .eval(&env, "(module m (export twice) (fun twice (x) (* 2 x)))")
+        .unwrap();
+    assert_eq!(evaluator.call::<f64, _>(&env, "m/twice", (4.0,)), Ok(8.0));
+    assert_eq!(evaluator.call::<f64, _>(&env, "math/abs", (-2.0,)), Ok(2.0));
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use owl::{
+    evaluator::{EvalError, Evaluator},
+    values::{
+        Env, FromValue, IntoValue,
+        Value::{self, Bool, Num, Str},
+    },
+};
+
+#[test]
+fn registering_closures() {
+    let mut evaluator = Evaluator::new();
+    evaluator.register_fn("clamp", |x: f64, lo: f64, hi: f64| x.max(lo).min(hi));
+    evaluator.register_fn("shout", |s: String| s.to_uppercase());
+    evaluator.register_fn("answer", || 42i64);
+    let env = Env::new();
+
+    assert_eq!(evaluator.eval(&env, "(clamp 12 0 10)"), Ok(Num(10.0)));
+    assert_eq!(evaluator.eval(&env, "(clamp (- 0 4) 0 10)"), Ok(Num(0.0)));
+    assert_eq!(
+        evaluator.eval(&env, r#"(shout "owl")"#),
+        Ok(Str("OWL".into()))
+    );
+    assert_eq!(evaluator.eval(&env, "(answer)"), Ok(Num(42.0)));
+}
+
+#[test]
+fn argument_type_errors_are_generated() {
+    let mut evaluator = Evaluator::new();
+    evaluator.register_fn("clamp", |x: f64, lo: f64, hi: f64| x.max(lo).min(hi));
+    evaluator.register_fn("nth-bit", |n: i64, bit: i64| (n >> bit) & 1 == 1);
+    let env = Env::new();
+
+    assert_eq!(
+        evaluator.eval(&env, r#"(clamp 1 "zero" 10)"#),
+        Err(EvalError::Type {
+            name: "clamp".into(),
+            position: 2,
+            expected: "number".into(),
+            got: "string".into(),
+        })
+    );
+    assert!(matches!(
+        evaluator.eval(&env, "(nth-bit 5.5 0)"),
+        Err(EvalError::Type { position: 1, .. })
+    ));
+    assert_eq!(evaluator.eval(&env, "(nth-bit 5 2)"), Ok(Bool(true)));
+    assert!(matches!(
+        evaluator.eval(&env, "(clamp 1 2)"),
+        Err(EvalError::Arity { got: 2, .. })
+    ));
+}
+
+#[test]
+fn converting_values() {
+    assert_eq!(i64::from_value(Num(3.0)), Some(3));
+    assert_eq!(i64::from_value(Num(3.5)), None);
+    assert_eq!(bool::from_value(Bool(true)), Some(true));
+    assert_eq!(Option::<f64>::from_value(Value::None), Some(None));
+    assert_eq!(Option::<f64>::from_value(Num(1.0)), Some(Some(1.0)));
+    assert_eq!(
+        Vec::<f64>::from_value(Value::list(vec![Num(1.0), Num(2.0)])),
+        Some(vec![1.0, 2.0])
+    );
+    assert_eq!(
+        Vec::<f64>::from_value(Value::list(vec![Str("a".into())])),
+        None
+    );
+    assert_eq!(Vec::<Option<String>>::expected(), "list of string or none");
+
+    let mut map = HashMap::new();
+    map.insert("b".to_string(), 2.0);
+    map.insert("a".to_string(), 1.0);
+    let value = map.clone().into_value();
+    assert_eq!(
+        value,
+        Value::list(vec![
+            Value::list(vec![Str("a".into()), Num(1.0)]),
+            Value::list(vec![Str("b".into()), Num(2.0)]),
+        ])
+    );
+    assert_eq!(HashMap::<String, f64>::from_value(value), Some(map));
+}
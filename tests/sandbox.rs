@@ -0,0 +1,148 @@
+use std::{env, fs, process};
+
+use owl::{
+    evaluator::{Capabilities, Capability, EvalError, Evaluator},
+    values::{
+        Env,
+        Value::{self, Num, Str},
+    },
+};
+
+fn denied(name: &str, capability: Capability) -> Result<Value, EvalError> {
+    Err(EvalError::PermissionDenied {
+        name: name.into(),
+        capability,
+    })
+}
+
+#[test]
+fn sandboxed_evaluators_cannot_read_files() {
+    let path = env::temp_dir().join(format!("owl-{}-secret.txt", process::id()));
+    fs::write(&path, "secret").unwrap();
+    let path = path.to_str().unwrap().to_string();
+
+    let evaluator = Evaluator::sandboxed();
+    let env = Env::new();
+    let attempts = [
+        format!(r#"(read-file "{}")"#, path),
+        format!(r#"(def p "{}") (eval "(read-file p)")"#, path),
+        format!(r#"(eval '(read-file "{}"))"#, path),
+        format!(r#"(map read-file (list "{}"))"#, path),
+        format!(r#"(def f read-file) (f "{}")"#, path),
+    ];
+    for code in &attempts {
+        assert_eq!(
+            evaluator.eval(&env, code),
+            denied("read-file", Capability::Files),
+            "{}",
+            code
+        );
+    }
+    assert_eq!(
+        evaluator.call::<Value, _>(&env, "read-file", (path.as_str(),)),
+        denied("read-file", Capability::Files)
+    );
+    assert_eq!(
+        Evaluator::new().eval(&env, format!(r#"(read-file "{}")"#, path)),
+        Ok(Str("secret".into()))
+    );
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn sandboxed_evaluators_keep_pure_intrinsics() {
+    let mut evaluator = Evaluator::sandboxed();
+    let output = evaluator.capture_output();
+    evaluator.register_fn("host-answer", || 42i64);
+    let env = Env::new();
+
+    assert_eq!(
+        evaluator.eval(&env, r#"(println (upcase "ok") (sqrt 16) (length '(1 2)))"#),
+        Ok(Value::None)
+    );
+    assert_eq!(output.contents(), "OK 4 2\n");
+    assert_eq!(evaluator.eval(&env, "(host-answer)"), Ok(Num(42.0)));
+
+    assert_eq!(
+        evaluator.eval(&env, r#"(getenv "HOME")"#),
+        denied("getenv", Capability::Os)
+    );
+    assert_eq!(
+        evaluator.eval(&env, r#"(run "ls")"#),
+        denied("run", Capability::Process)
+    );
+    assert_eq!(
+        evaluator.eval(&env, "(exit 1)"),
+        denied("exit", Capability::Process)
+    );
+}
+
+#[test]
+fn choosing_capabilities() {
+    let core = Evaluator::with_capabilities(Capabilities::none().with(Capability::Core));
+    let env = Env::new();
+    assert_eq!(core.eval(&env, "(+ 1 2)"), Ok(Num(3.0)));
+    assert_eq!(
+        core.eval(&env, "(sqrt 4)"),
+        denied("sqrt", Capability::Math)
+    );
+    assert_eq!(
+        core.eval(&env, r#"(trim " x ")"#),
+        denied("trim", Capability::Strings)
+    );
+    assert_eq!(
+        core.eval(&env, r#"(print "x")"#),
+        denied("print", Capability::Console)
+    );
+
+    let capabilities = Capabilities::all().without(Capability::Process);
+    assert!(capabilities.allows(Capability::Files));
+    assert!(!capabilities.allows(Capability::Process));
+    let evaluator = Evaluator::with_capabilities(capabilities);
+    assert_eq!(evaluator.capabilities(), capabilities);
+    assert_eq!(
+        evaluator.eval(&env, r#"(run "ls")"#),
+        denied("run", Capability::Process)
+    );
+    assert!(matches!(evaluator.eval(&env, "(now)"), Ok(Num(_))));
+}
+
+#[test]
+fn permission_errors_are_readable() {
+    let error = Evaluator::sandboxed()
+        .eval(&Env::new(), r#"(delete-file "x")"#)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "permission denied: delete-file needs the files capability"
+    );
+}
+
+#[test]
+fn os_and_process_intrinsics() {
+    let evaluator = Evaluator::new();
+    let env = Env::new();
+    env::set_var("OWL_SANDBOX_TEST", "yes");
+    assert_eq!(
+        evaluator.eval(&env, r#"(getenv "OWL_SANDBOX_TEST")"#),
+        Ok(Str("yes".into()))
+    );
+    assert_eq!(
+        evaluator.eval(&env, r#"(getenv "OWL_SANDBOX_UNSET")"#),
+        Ok(Value::None)
+    );
+    if cfg!(unix) {
+        assert_eq!(
+            evaluator.eval(&env, r#"(run "sh" "-c" "echo hi; exit 3")"#),
+            Ok(Value::list(vec![
+                Num(3.0),
+                Str("hi\n".into()),
+                Str("".into())
+            ]))
+        );
+    }
+    assert!(matches!(
+        evaluator.eval(&env, r#"(run "owl-no-such-program")"#),
+        Err(EvalError::Io(_))
+    ));
+}
@@ -0,0 +1,100 @@
+use owl::{
+    evaluator::{eval, EvalError},
+    values::Value::{self, Bool, Num, Str},
+};
+
+fn strs(xs: &[&str]) -> Value {
+    Value::list(xs.iter().map(|x| Str(x.to_string())).collect())
+}
+
+#[test]
+fn measuring_and_slicing_by_character() {
+    assert_eq!(eval(r#"(string-length "héllo")"#), Ok(Num(5.0)));
+    assert_eq!(eval(r#"(string-length "🦉")"#), Ok(Num(1.0)));
+    assert_eq!(eval(r#"(substring "héllo" 1 3)"#), Ok(Str("él".into())));
+    assert_eq!(eval(r#"(substring "héllo" 2)"#), Ok(Str("llo".into())));
+    assert!(matches!(
+        eval(r#"(substring "abc" 2 5)"#),
+        Err(EvalError::Native(_))
+    ));
+    assert_eq!(eval(r#"(index-of "naïve owl" "owl")"#), Ok(Num(6.0)));
+    assert_eq!(eval(r#"(index-of "owl" "cat")"#), Ok(Value::None));
+}
+
+#[test]
+fn combining_and_splitting() {
+    assert_eq!(
+        eval(r#"(string-append "a" "ß" "c")"#),
+        Ok(Str("aßc".into()))
+    );
+    assert_eq!(
+        eval(r#"(string-append "a" 1)"#),
+        Err(EvalError::Type {
+            name: "string-append".into(),
+            position: 2,
+            expected: "string".into(),
+            got: "number".into(),
+        })
+    );
+    assert_eq!(
+        eval(r#"(split "a,b,,c" ",")"#),
+        Ok(strs(&["a", "b", "", "c"]))
+    );
+    assert_eq!(eval(r#"(split "añb" "")"#), Ok(strs(&["a", "ñ", "b"])));
+    assert_eq!(
+        eval(r#"(join (list "x" "y" "z") ", ")"#),
+        Ok(Str("x, y, z".into()))
+    );
+    assert_eq!(eval(r#"(string->list "ab✓")"#), Ok(strs(&["a", "b", "✓"])));
+    assert_eq!(eval(r#"(string->list "")"#), Ok(strs(&[])));
+}
+
+#[test]
+fn transforming() {
+    assert_eq!(eval("(trim \"  owl \n\")"), Ok(Str("owl".into())));
+    assert_eq!(eval(r#"(upcase "straße")"#), Ok(Str("STRASSE".into())));
+    assert_eq!(eval(r#"(downcase "ÉCOLE")"#), Ok(Str("école".into())));
+    assert_eq!(
+        eval(r#"(replace "a-b-c" "-" "→")"#),
+        Ok(Str("a→b→c".into()))
+    );
+}
+
+#[test]
+fn searching() {
+    assert_eq!(eval(r#"(starts-with? "über" "üb")"#), Ok(Bool(true)));
+    assert_eq!(eval(r#"(ends-with? "log.txt" ".txt")"#), Ok(Bool(true)));
+    assert_eq!(eval(r#"(contains? "owl" "x")"#), Ok(Bool(false)));
+}
+
+#[test]
+fn converting_numbers() {
+    assert_eq!(eval(r#"(string->number " 4.5 ")"#), Ok(Num(4.5)));
+    assert_eq!(eval(r#"(string->number "four")"#), Ok(Value::None));
+    assert_eq!(eval("(number->string 3)"), Ok(Str("3".into())));
+    assert_eq!(eval("(number->string -0.5)"), Ok(Str("-0.5".into())));
+}
+
+#[test]
+fn formatting() {
+    assert_eq!(
+        eval(r#"(format "{} + {} = {}" 1 2 (+ 1 2))"#),
+        Ok(Str("1 + 2 = 3".into()))
+    );
+    assert_eq!(
+        eval(r#"(format "{1} {0} {1}" "a" :b)"#),
+        Ok(Str(":b a :b".into()))
+    );
+    assert_eq!(
+        eval(r#"(format "{{{}}} ✓" (list 1 2))"#),
+        Ok(Str("{(1 2)} ✓".into()))
+    );
+    assert!(matches!(
+        eval(r#"(format "{} {}" 1)"#),
+        Err(EvalError::Native(_))
+    ));
+    assert!(matches!(
+        eval(r#"(format "{x}" 1)"#),
+        Err(EvalError::Native(_))
+    ));
+}
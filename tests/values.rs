@@ -1,8 +1,12 @@
 use core::panic;
 
 use owl::{
-    reader::{Reader, ReaderError},
-    values::Value::{self, Bool, List, Num, Str, Sym},
+    evaluator::eval,
+    reader::{Reader, ReaderError, MAX_NESTING},
+    values::{
+        car, cdr, cons, Source, Symbol,
+        Value::{self, Bool, List, Num, Str, Sym},
+    },
 };
 
 #[test]
@@ -14,6 +18,7 @@ fn skipping_whitespace() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn reading_numbers() {
     let code = String::from(r"1 123 -54 0.0 .3 -.3 3.1415926 ");
     let mut reader = Reader::new();
@@ -25,6 +30,13 @@ fn reading_numbers() {
     assert_eq!(reader.read(&code).unwrap(), Num(-0.3));
     assert_eq!(reader.read(&code).unwrap(), Num(3.1415926));
 
+    let code = String::from("1e3 2.5E-2 -1e+2 4e");
+    reader.reset();
+    assert_eq!(reader.read(&code).unwrap(), Num(1000.0));
+    assert_eq!(reader.read(&code).unwrap(), Num(0.025));
+    assert_eq!(reader.read(&code).unwrap(), Num(-100.0));
+    assert_eq!(reader.read(&code).unwrap(), Num(4.0));
+
     reader.reset();
     let error_code = String::from("34.41.123");
     assert_eq!(
@@ -65,7 +77,7 @@ fn reading_lists() {
     let mut reader = Reader::new();
     match reader.read(&code).unwrap() {
         List(xs) => {
-            let a1 = xs.get(0).unwrap();
+            let a1 = xs.first().unwrap();
             let a2 = xs.get(1).unwrap();
             let a3 = xs.get(2).unwrap();
             let a4 = xs.get(3).unwrap();
@@ -87,12 +99,12 @@ fn reading_lists() {
     let def = reader.read(&code2).unwrap();
     assert_eq!(
         def,
-        Value::List(vec![
+        Value::list(vec![
             Sym("def".into()),
             Sym("x".into()),
-            Value::List(vec![
+            Value::list(vec![
                 Sym("=".into()),
-                Value::List(vec![Sym("+".into()), Num(1.0), Num(2.0),]),
+                Value::list(vec![Sym("+".into()), Num(1.0), Num(2.0),]),
                 Num(3.0)
             ])
         ])
@@ -109,13 +121,30 @@ fn reading_lists() {
     )
 }
 
+#[test]
+fn deeply_nested_forms_are_rejected() {
+    let nested = |open: &str, close: &str, depth: usize| open.repeat(depth) + &close.repeat(depth);
+    let mut reader = Reader::new();
+    assert!(reader.read(&nested("(", ")", MAX_NESTING)).is_ok());
+    for code in [
+        nested("(", ")", MAX_NESTING + 1),
+        nested("(+ 1 ", ")", 100_000),
+        nested("{", "}", 100_000),
+        nested("f(", ")", 100_000),
+        nested("'", "", 100_000) + "x",
+    ] {
+        reader.reset();
+        assert_eq!(reader.read(&code), Err(ReaderError::NestedTooDeeply));
+    }
+}
+
 #[test]
 fn reading_function_calls() {
     let code = String::from("a(1 2 3)");
     let mut reader = Reader::new();
     match reader.read(&code).unwrap() {
         List(xs) => {
-            let a1 = xs.get(0).unwrap();
+            let a1 = xs.first().unwrap();
             let a2 = xs.get(1).unwrap();
             let a3 = xs.get(2).unwrap();
             let a4 = xs.get(3).unwrap();
@@ -141,7 +170,7 @@ fn reading_do_blocks() {
     let mut reader = Reader::new();
     match reader.read(&code).unwrap() {
         List(xs) => {
-            let a0 = xs.get(0).unwrap();
+            let a0 = xs.first().unwrap();
             let a1 = xs.get(1).unwrap();
             let a2 = xs.get(2).unwrap();
             let a3 = xs.get(3).unwrap();
@@ -159,3 +188,170 @@ fn reading_do_blocks() {
     let error_code = String::from("{a {b c}");
     assert_eq!(reader.read(&error_code), Err(ReaderError::UnbalancedBraces))
 }
+
+#[test]
+fn reading_empty_lists_and_trailing_whitespace() {
+    let code = String::from("(f () { })  \n");
+    let mut reader = Reader::new();
+    assert_eq!(
+        reader.read_script(&code).unwrap(),
+        Value::list(vec![
+            Sym("do".into()),
+            Value::list(vec![
+                Sym("f".into()),
+                Value::list(vec![]),
+                Value::list(vec![Sym("do".into())])
+            ])
+        ])
+    );
+}
+
+#[test]
+fn interning_symbols() {
+    let a = Symbol::new("interned-name");
+    assert_eq!(a, Symbol::from("interned-name".to_string()));
+    assert_ne!(a, Symbol::new("other-name"));
+    assert_eq!(a.as_str(), "interned-name");
+    assert_eq!(Symbol::new("do"), Symbol::DO);
+    assert_eq!(Symbol::FN, "fn");
+
+    let mut reader = Reader::new();
+    assert_eq!(reader.read("interned-name").unwrap(), Sym(a));
+}
+
+#[test]
+fn symbols_interned_on_other_threads_keep_their_names() {
+    let before = Symbol::new("seen-before");
+    assert_eq!(before.as_str(), "seen-before");
+    let sym = std::thread::spawn(|| Symbol::new("from-another/thread"))
+        .join()
+        .unwrap();
+    assert_eq!(sym.as_str(), "from-another/thread");
+    assert_eq!(
+        sym.qualified(),
+        Some(("from-another".into(), "thread".into()))
+    );
+    assert_eq!(Symbol::new("from-another/thread"), sym);
+}
+
+#[test]
+fn qualified_symbols_split_at_the_last_separator() {
+    let parts = |name: &str| {
+        Symbol::new(name)
+            .qualified()
+            .map(|(ns, name)| (ns.as_str(), name.as_str()))
+    };
+    assert_eq!(parts("math/sqrt"), Some(("math", "sqrt")));
+    assert_eq!(parts("str.split"), Some(("str", "split")));
+    assert_eq!(parts("a.b/c"), Some(("a.b", "c")));
+    assert_eq!(parts("/"), None);
+    assert_eq!(parts("plain"), None);
+    assert_eq!(parts("trailing/"), None);
+}
+
+#[test]
+fn reading_and_printing_dotted_pairs() {
+    let mut reader = Reader::new();
+    let pair = reader.read("(a . b)").unwrap();
+    assert_eq!(pair, cons(Sym("a".into()), Sym("b".into())));
+    assert_eq!(pair.to_string(), "(a . b)");
+
+    reader.reset();
+    let improper = reader.read("(1 2 . 3)").unwrap();
+    assert_eq!(improper.to_string(), "(1 2 . 3)");
+    match &improper {
+        List(xs) => {
+            assert_eq!(xs.len(), 2);
+            assert!(!xs.is_proper());
+            assert_eq!(xs.tail(), Some(&Num(3.0)));
+        }
+        x => panic!("Expected list but got {:?}", x),
+    }
+
+    reader.reset();
+    assert_eq!(reader.read("(1 . (2 3))").unwrap(), reader_value("(1 2 3)"));
+
+    for code in ["(a .)", "(a . b c)", "(. b)"] {
+        reader.reset();
+        assert_eq!(reader.read(code), Err(ReaderError::InvalidDottedPair));
+    }
+}
+
+fn reader_value(code: &str) -> Value {
+    Reader::new().read(code).unwrap()
+}
+
+#[test]
+fn lists_share_structure() {
+    let tail = reader_value("(2 3)");
+    let xs = cons(Num(1.0), tail.clone());
+    let ys = cons(Num(0.0), tail.clone());
+    assert_eq!(cdr(&xs), tail);
+    assert_eq!(cdr(&ys), tail);
+    assert_eq!(car(&xs), &Num(1.0));
+    assert_eq!(xs.to_string(), "(1 2 3)");
+    assert_eq!(car(&Value::list(vec![])), &Value::None);
+
+    // Walking a long list with cdr is linear, and dropping it does not
+    // recurse per element.
+    let mut long = Value::list((0..200_000).map(|n| Num(n as f64)).collect());
+    let mut count = 0;
+    while let List(xs) = &long {
+        if xs.is_empty() {
+            break;
+        }
+        count += 1;
+        long = cdr(&long);
+    }
+    assert_eq!(count, 200_000);
+    drop(Value::list((0..1_000_000).map(|n| Num(n as f64)).collect()));
+}
+
+#[test]
+fn reading_quotes() {
+    assert_eq!(
+        reader_value("'(a . b)"),
+        Value::list(vec![Sym("quote".into()), reader_value("(a . b)")])
+    );
+    assert_eq!(eval("'(a . b)"), Ok(reader_value("(a . b)")));
+    assert_eq!(eval("(quote (1 x))"), Ok(reader_value("(1 x)")));
+    assert_eq!(eval("'sym"), Ok(Sym("sym".into())));
+}
+
+#[test]
+fn reading_unicode() {
+    let code = String::from(r#"(café "naïve 🦉" λ)"#);
+    let mut reader = Reader::new();
+    assert_eq!(
+        reader.read(&code).unwrap(),
+        Value::list(vec![
+            Sym("café".into()),
+            Str("naïve 🦉".into()),
+            Sym("λ".into())
+        ])
+    );
+}
+
+#[test]
+fn lists_remember_where_they_were_read() {
+    let source = Source::new("test.owl", "(a\n  (b c) 'd)");
+    let mut reader = Reader::with_source(source.clone());
+    let form = reader.read(&source.text).unwrap();
+    let List(outer) = &form else {
+        panic!("expected a list");
+    };
+    let span = outer.span().unwrap();
+    assert_eq!(span.text(), "(a\n  (b c) 'd)");
+    assert_eq!(span.to_string(), "test.owl:1:1");
+    let List(inner) = outer.get(1).unwrap() else {
+        panic!("expected a list");
+    };
+    assert_eq!(inner.span().unwrap().to_string(), "test.owl:2:3");
+    assert_eq!(source.line_col(source.text.len()), (2, 12));
+
+    // Spans do not take part in equality, and plain readers record none.
+    let plain = Reader::new().read(&source.text).unwrap();
+    assert_eq!(plain, form);
+    let List(plain) = plain else { unreachable!() };
+    assert!(plain.span().is_none());
+}